@@ -1,6 +1,6 @@
 use std::sync::Arc;
 
-use pyo3::prelude::*;
+use pyo3::{exceptions::PyValueError, prelude::*};
 use pyo3_polars::PyDataFrame;
 use slp_parse::prelude::*;
 
@@ -104,6 +104,21 @@ impl PyPlayer {
     fn get_is_winner(&self) -> PyResult<Option<bool>> {
         Ok(self.player.is_winner)
     }
+    /// List of (css character id, start frame, end frame) tuples. The end frame is exclusive
+    #[getter]
+    fn get_transformations(&self) -> PyResult<Vec<(u8, i32, i32)>> {
+        self.player
+            .transformations
+            .iter()
+            .map(|x| {
+                let character = x
+                    .character
+                    .try_as_css()
+                    .map_err(|e| PyValueError::new_err(e.to_string()))?;
+                Ok((character, x.frames.start, x.frames.end))
+            })
+            .collect()
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -119,6 +134,16 @@ impl PyStats {
         Ok(PyDataFrame((&self.stats.input).into()))
     }
 
+    #[getter]
+    fn get_input_timeline(&self) -> PyResult<PyDataFrame> {
+        Ok(PyDataFrame(self.stats.input.timeline_df()))
+    }
+
+    #[getter]
+    fn get_conversions(&self) -> PyResult<PyDataFrame> {
+        Ok(PyDataFrame((&self.stats.conversions).into()))
+    }
+
     #[getter]
     fn get_l_cancel(&self) -> PyResult<Option<PyDataFrame>> {
         Ok(self
@@ -139,4 +164,96 @@ impl PyStats {
     fn get_tech(&self) -> PyResult<Option<PyDataFrame>> {
         Ok(self.stats.tech.as_ref().map(|df| PyDataFrame(df.into())))
     }
+    #[getter]
+    fn get_edgeguard(&self) -> PyResult<Option<PyDataFrame>> {
+        Ok(self
+            .stats
+            .edgeguard
+            .as_ref()
+            .map(|df| PyDataFrame(df.into())))
+    }
+    #[getter]
+    fn get_grab(&self) -> PyResult<Option<PyDataFrame>> {
+        Ok(self.stats.grab.as_ref().map(|df| PyDataFrame(df.into())))
+    }
+    #[getter]
+    fn get_moves(&self) -> PyResult<Option<PyDataFrame>> {
+        Ok(self.stats.moves.as_ref().map(|df| PyDataFrame(df.into())))
+    }
+    #[getter]
+    fn get_hits(&self) -> PyResult<Option<PyDataFrame>> {
+        Ok(self.stats.hits.as_ref().map(|df| PyDataFrame(df.into())))
+    }
+    #[getter]
+    fn get_nana_defense(&self) -> PyResult<Option<PyDataFrame>> {
+        Ok(self
+            .stats
+            .nana_defense
+            .as_ref()
+            .map(|df| PyDataFrame(df.into())))
+    }
+    #[getter]
+    fn get_nana_tech(&self) -> PyResult<Option<PyDataFrame>> {
+        Ok(self
+            .stats
+            .nana_tech
+            .as_ref()
+            .map(|df| PyDataFrame(df.into())))
+    }
+    #[getter]
+    fn get_nana_kills(&self) -> PyResult<Option<PyDataFrame>> {
+        Ok(self
+            .stats
+            .nana_kills
+            .as_ref()
+            .map(|df| PyDataFrame(df.into())))
+    }
+    #[getter]
+    fn get_movement(&self) -> PyResult<Option<PyDataFrame>> {
+        Ok(self
+            .stats
+            .movement
+            .as_ref()
+            .map(|df| PyDataFrame(df.into())))
+    }
+    #[getter]
+    fn get_controller(&self) -> PyResult<Option<PyDataFrame>> {
+        Ok(self
+            .stats
+            .controller
+            .as_ref()
+            .map(|df| PyDataFrame(df.into())))
+    }
+    #[getter]
+    fn get_ics(&self) -> PyResult<Option<PyDataFrame>> {
+        Ok(self.stats.ics.as_ref().map(|df| PyDataFrame(df.into())))
+    }
+    #[getter]
+    fn get_kills(&self) -> PyResult<Option<PyDataFrame>> {
+        Ok(self.stats.kills.as_ref().map(|df| PyDataFrame(df.into())))
+    }
+    #[getter]
+    fn get_ledge(&self) -> PyResult<Option<PyDataFrame>> {
+        Ok(self.stats.ledge.as_ref().map(|df| PyDataFrame(df.into())))
+    }
+    #[getter]
+    fn get_shield(&self) -> PyResult<Option<PyDataFrame>> {
+        Ok(self.stats.shield.as_ref().map(|df| PyDataFrame(df.into())))
+    }
+    #[getter]
+    fn get_powershield(&self) -> PyResult<Option<PyDataFrame>> {
+        Ok(self
+            .stats
+            .powershield
+            .as_ref()
+            .map(|df| PyDataFrame(df.into())))
+    }
+    #[getter]
+    fn get_recovery(&self) -> PyResult<Option<PyDataFrame>> {
+        Ok(self
+            .stats
+            .recovery
+            .as_ref()
+            .map(|df| PyDataFrame(df.into())))
+    }
 }
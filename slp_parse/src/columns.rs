@@ -133,6 +133,7 @@ pub enum LCancelStats {
     LCancel,
     TriggerFrame,
     Position,
+    PlatformHeight,
     Fastfall,
     InputDuringHitlag,
 }
@@ -146,6 +147,18 @@ pub enum InputStats {
     APM,
     TriggerPref,
     JumpPref,
+    Buttons,
+    StickFlicks,
+    NeutralAPM,
+    PunishAPM,
+    HitstunAPM,
+}
+
+#[derive(Debug, Clone, Copy, Display, EnumString, IntoStaticStr)]
+pub enum InputTimeline {
+    FrameIndex,
+    NewInput,
+    Phase,
 }
 
 #[derive(Debug, Clone, Copy, Display, EnumString, IntoStaticStr, VariantNames)]
@@ -154,6 +167,8 @@ pub enum DefenseStats {
     Stocks,
     Percent,
     DamageTaken,
+    UnstaledDamage,
+    Staleness,
     LastHitBy,
     StateBeforeHit,
     Grounded,
@@ -184,6 +199,11 @@ pub enum WavedashStats {
     Angle,
     Direction,
     StartPosition,
+    AirdodgeDelay,
+    AngleDeviation,
+    Distance,
+    Ground,
+    OutOfShield,
 }
 
 // #[derive(Debug, Clone, Copy, Display, EnumString, IntoStaticStr)]
@@ -209,6 +229,7 @@ pub enum TechStats {
     Punished,
     Position,
     Location,
+    PlatformHeight,
     MissedTech,
     Lockout,
     TowardsCenter,
@@ -218,3 +239,172 @@ pub enum TechStats {
     OpntDistance,
     DuringHitlag,
 }
+
+#[derive(Debug, Clone, Copy, Display, EnumString, IntoStaticStr)]
+pub enum RecoveryStats {
+    FrameIndex,
+    Stocks,
+    Percent,
+    LastHitBy,
+    Side,
+    JumpsRemaining,
+    HitstunEnd,
+    DistFromStage,
+    Options,
+    UpB,
+    PseudoJump,
+    Duration,
+    Outcome,
+    StockLost,
+    RandallInvolved,
+}
+
+#[derive(Debug, Clone, Copy, Display, EnumString, IntoStaticStr)]
+pub enum EdgeguardStats {
+    FrameIndex,
+    OpntStocks,
+    StartPercent,
+    EndPercent,
+    SentOffstageBy,
+    Options,
+    TimeToLedge,
+    Duration,
+    Outcome,
+}
+
+#[derive(Debug, Clone, Copy, Display, EnumString, IntoStaticStr)]
+pub enum LedgeStats {
+    FrameIndex,
+    Stocks,
+    Percent,
+    Position,
+    FramesOnLedge,
+    Option,
+    Galint,
+    Punished,
+}
+
+#[derive(Debug, Clone, Copy, Display, EnumString, IntoStaticStr)]
+pub enum ConversionStats {
+    FrameIndex,
+    EndFrame,
+    OpntStocks,
+    StartPercent,
+    EndPercent,
+    OpeningAttack,
+    OpeningType,
+    HitCount,
+    DidKill,
+}
+
+#[derive(Debug, Clone, Copy, Display, EnumString, IntoStaticStr)]
+pub enum KillStats {
+    FrameIndex,
+    Stocks,
+    Percent,
+    KilledBy,
+    Blastzone,
+    SelfDestruct,
+    OpntPercent,
+    EarlyKill,
+    SurvivedHighPercent,
+}
+
+#[derive(Debug, Clone, Copy, Display, EnumString, IntoStaticStr)]
+pub enum ShieldStats {
+    FrameIndex,
+    Stocks,
+    Attack,
+    ShieldHealth,
+    ShieldDamage,
+    Powershield,
+    Poked,
+    Broken,
+    OoSOption,
+    OoSDelay,
+}
+
+#[derive(Debug, Clone, Copy, Display, EnumString, IntoStaticStr)]
+pub enum PowershieldStats {
+    FrameIndex,
+    Stocks,
+    Attack,
+    PsType,
+    ShieldFrame,
+    TriggerFrame,
+    FrameAdvantage,
+}
+
+#[derive(Debug, Clone, Copy, Display, EnumString, IntoStaticStr)]
+pub enum GrabStats {
+    FrameIndex,
+    Stocks,
+    OpntPercent,
+    GrabType,
+    Success,
+    Pummels,
+    Throw,
+    Techchase,
+    Regrab,
+    Chaingrab,
+    Damage,
+}
+
+#[derive(Debug, Clone, Copy, Display, EnumString, IntoStaticStr)]
+pub enum MoveStats {
+    Attack,
+    Started,
+    Hit,
+    Shielded,
+    Whiffed,
+    TotalDamage,
+    AvgDamage,
+    AvgUnstaledDamage,
+    AvgStaleness,
+}
+
+#[derive(Debug, Clone, Copy, Display, EnumString, IntoStaticStr)]
+pub enum HitStats {
+    FrameIndex,
+    Attacker,
+    Source,
+    Item,
+    SpawnId,
+    Victim,
+    VictimNana,
+    Attack,
+    Damage,
+    HitlagFrames,
+    Knockback,
+}
+
+#[derive(Debug, Clone, Copy, Display, EnumString, IntoStaticStr)]
+pub enum IcsStats {
+    FrameIndex,
+    Stocks,
+    Event,
+    Duration,
+}
+
+#[derive(Debug, Clone, Copy, Display, EnumString, IntoStaticStr)]
+pub enum MovementStats {
+    FrameIndex,
+    Stocks,
+    Movement,
+    Success,
+    ControllerFix,
+    Dashes,
+    Cadence,
+}
+
+#[derive(Debug, Clone, Copy, Display, EnumString, IntoStaticStr)]
+pub enum ControllerDiagnostics {
+    ControllerType,
+    Gate,
+    Snapbacks,
+    Jitter,
+    LTrigger,
+    RTrigger,
+    LMax,
+    RMax,
+}
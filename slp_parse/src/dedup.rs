@@ -0,0 +1,335 @@
+use std::{collections::HashMap, hash::Hasher, sync::Arc};
+
+use anyhow::{ensure, Result};
+use fnv::FnvHasher;
+use ssbm_utils::enums::{Character, Port, StageID};
+
+use crate::{
+    columns::PostFrame,
+    events::post_frame::PostRow,
+    game::{Game, GameMetadata, GameStub},
+};
+
+/// Number of frames of input data hashed when a replay doesn't have a match id
+pub const IDENTITY_FRAMES: usize = 600;
+
+/// Positional difference allowed between 2 copies of the same game before they're considered
+/// desynced
+pub const DESYNC_TOLERANCE: f32 = 0.001;
+
+/// Uniquely identifies a single game, regardless of whose computer it was recorded on.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum GameId {
+    /// Replays >= 3.14.0 played on netplay contain a match id that is shared by both players
+    Match {
+        match_id: Arc<String>,
+        game_number: u32,
+        tiebreak_number: u32,
+    },
+    /// Hash of the random seed, the players, and (if available) the first `IDENTITY_FRAMES` frames
+    /// of inputs. Stable across platforms and Rust versions, so it's safe to persist
+    Hashed(u64),
+}
+
+/// Starts a hash of the fields shared by `Game` and `GameStub`.
+///
+/// Neither `DefaultHasher` nor the std `Hash` impls (native-endian integers, length prefixes, enum
+/// discriminants) are guaranteed to be stable across Rust versions, so everything is written as
+/// explicit little-endian bytes into an FNV-1a hasher instead
+fn base_hasher(random_seed: u32, stage: StageID) -> FnvHasher {
+    let mut hasher = FnvHasher::default();
+    hasher.write(&random_seed.to_le_bytes());
+    hasher.write(&(stage as u16).to_le_bytes());
+    hasher
+}
+
+#[inline]
+fn hash_player(hasher: &mut FnvHasher, port: Port, character: Character) {
+    hasher.write_u8(port as u8);
+    hasher.write_u8(character.as_internal());
+}
+
+/// Whether 2 copies of the same game stayed in sync
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SyncStatus {
+    /// The copies match on every frame
+    Synced,
+    /// The melee frame index at which the copies first diverge
+    Desynced(i32),
+    /// The copies can't be compared frame-by-frame, e.g. because their ports or start frames differ
+    Incomparable,
+    /// The container doesn't have frame data
+    NoFrameData,
+}
+
+pub trait Identity: GameMetadata {
+    fn identity(&self) -> GameId;
+
+    /// Checks whether and where the 2 copies of the game diverge
+    fn first_desync(&self, other: &Self) -> SyncStatus;
+}
+
+/// Shared by both Game and GameStub. Returns None if there's no match id
+fn match_id<G: GameMetadata>(game: &G) -> Option<GameId> {
+    let match_id = game.match_id();
+    if match_id.is_empty() {
+        return None;
+    }
+
+    Some(GameId::Match {
+        match_id,
+        game_number: game.game_number().unwrap_or(0),
+        tiebreak_number: game.tiebreak_number().unwrap_or(0),
+    })
+}
+
+impl Identity for Game {
+    fn identity(&self) -> GameId {
+        if let Some(id) = match_id(self) {
+            return id;
+        }
+
+        let mut hasher = base_hasher(self.random_seed(), self.stage());
+
+        for player in &self.players {
+            hash_player(&mut hasher, player.port, player.character);
+
+            let pre = &player.frames.pre;
+            let len = pre.len().min(IDENTITY_FRAMES);
+            for buttons in &pre.engine_buttons[..len] {
+                hasher.write(&buttons.to_le_bytes());
+            }
+            for stick in pre.joystick[..len].iter().chain(&pre.cstick[..len]) {
+                hasher.write(&stick.x.to_bits().to_le_bytes());
+                hasher.write(&stick.y.to_bits().to_le_bytes());
+            }
+        }
+
+        GameId::Hashed(hasher.finish())
+    }
+
+    fn first_desync(&self, other: &Self) -> SyncStatus {
+        match compare_replays(self, other) {
+            Ok(report) => report
+                .first_divergence
+                .map_or(SyncStatus::Synced, SyncStatus::Desynced),
+            Err(_) => SyncStatus::Incomparable,
+        }
+    }
+}
+
+impl Identity for GameStub {
+    /// Stubs don't have frame data, so replays without a match id can only be identified by their
+    /// random seed, stage, and players
+    fn identity(&self) -> GameId {
+        if let Some(id) = match_id(self) {
+            return id;
+        }
+
+        let mut hasher = base_hasher(self.random_seed(), self.stage());
+
+        for player in &self.players {
+            hash_player(&mut hasher, player.port, player.character);
+            // the length separates "ABC#1" + "" from "ABC" + "#1"
+            let code = player.connect_code.as_deref().unwrap_or_default();
+            hasher.write(&(code.len() as u32).to_le_bytes());
+            hasher.write(code.as_bytes());
+        }
+
+        GameId::Hashed(hasher.finish())
+    }
+
+    fn first_desync(&self, _other: &Self) -> SyncStatus {
+        SyncStatus::NoFrameData
+    }
+}
+
+/// A game that was removed by `dedup` because an identical copy was already present
+#[derive(Debug, Clone)]
+pub struct Duplicate<G> {
+    /// Index into the deduplicated Vec of the copy that was kept
+    pub kept: usize,
+    pub removed: G,
+    /// Whether the removed copy stayed in sync with the kept copy
+    pub desync: SyncStatus,
+}
+
+/// Removes duplicate copies of the same game (e.g. when both players' replays are in the same
+/// folder). The first copy encountered is kept and order is otherwise preserved.
+///
+/// Returns the deduplicated games and the removed copies.
+pub fn dedup<G: Identity>(games: Vec<G>) -> (Vec<G>, Vec<Duplicate<G>>) {
+    let mut seen: HashMap<GameId, usize> = HashMap::new();
+    let mut result: Vec<G> = Vec::with_capacity(games.len());
+    let mut duplicates = Vec::new();
+
+    for game in games {
+        let id = game.identity();
+        match seen.get(&id) {
+            Some(&kept) => {
+                let desync = result[kept].first_desync(&game);
+                duplicates.push(Duplicate {
+                    kept,
+                    removed: game,
+                    desync,
+                });
+            }
+            None => {
+                seen.insert(id, result.len());
+                result.push(game);
+            }
+        }
+    }
+
+    (result, duplicates)
+}
+
+// ---------------------------------------------------------------------------------------------- //
+//                                         Desync Reports                                         //
+// ---------------------------------------------------------------------------------------------- //
+
+/// A single column of a single player's `PostFrames` that differs between 2 copies of a game
+#[derive(Debug, Clone)]
+pub struct Divergence {
+    pub port: Port,
+    /// One of `Position`, `ActionState` or `Percent`. `FrameIndex` if one copy ends before the other
+    pub column: PostFrame,
+    /// Melee frame index of the first frame this column diverged on
+    pub frame_index: i32,
+    /// Difference on the first divergent frame. Positions use the distance between the 2 points,
+    /// action states are the difference of the raw state IDs, and frame indexes are the number of
+    /// frames missing from the shorter copy
+    pub difference: f32,
+    /// Largest difference across the whole game
+    pub max_difference: f32,
+    /// Total number of frames on which this column diverged
+    pub frame_count: usize,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ReplayComparison {
+    /// Melee frame index of the first frame where any column diverged. None if the games match
+    pub first_divergence: Option<i32>,
+    pub divergences: Vec<Divergence>,
+    /// The full post-frame of each player on the first divergent frame as (a, b)
+    pub first_rows: Vec<(Port, PostRow, PostRow)>,
+    /// (a.total_frames(), b.total_frames()). Only frames present in both copies are compared, a copy
+    /// that ends early diverges on the first frame it's missing
+    pub total_frames: (usize, usize),
+}
+
+/// Compares the `PostFrames` of 2 recordings of the same game, reporting every position, action
+/// state, and percent that differs by more than `DESYNC_TOLERANCE`.
+///
+/// Errors if the 2 games don't have the same ports.
+pub fn compare_replays(a: &Game, b: &Game) -> Result<ReplayComparison> {
+    let mut result = ReplayComparison {
+        total_frames: (a.total_frames(), b.total_frames()),
+        ..Default::default()
+    };
+
+    for player_a in &a.players {
+        let player_b = b.player_by_port(player_a.port)?;
+        let port = player_a.port;
+
+        let post_a = &player_a.frames.post;
+        let post_b = &player_b.frames.post;
+
+        ensure!(
+            post_a.frame_index.first() == post_b.frame_index.first(),
+            "Replays do not start on the same frame"
+        );
+
+        let mut columns: [Option<Divergence>; 3] = [None, None, None];
+        let overlap = post_a.len().min(post_b.len());
+
+        for i in 0..overlap {
+            let pos_a = post_a.position[i];
+            let pos_b = post_b.position[i];
+            let diffs = [
+                (
+                    PostFrame::Position,
+                    ((pos_a.x - pos_b.x).powi(2) + (pos_a.y - pos_b.y).powi(2)).sqrt(),
+                ),
+                (
+                    PostFrame::ActionState,
+                    (post_a.action_state[i] as f32 - post_b.action_state[i] as f32).abs(),
+                ),
+                (
+                    PostFrame::Percent,
+                    (post_a.percent[i] - post_b.percent[i]).abs(),
+                ),
+            ];
+
+            for (column, (kind, diff)) in columns.iter_mut().zip(diffs) {
+                if diff <= DESYNC_TOLERANCE {
+                    continue;
+                }
+
+                let div = column.get_or_insert_with(|| Divergence {
+                    port,
+                    column: kind,
+                    frame_index: post_a.frame_index[i],
+                    difference: diff,
+                    max_difference: 0.0,
+                    frame_count: 0,
+                });
+                div.max_difference = div.max_difference.max(diff);
+                div.frame_count += 1;
+            }
+        }
+
+        let mut divs = columns.into_iter().flatten().collect::<Vec<_>>();
+
+        if post_a.len() != post_b.len() {
+            let missing = post_a.len().abs_diff(post_b.len());
+            divs.push(Divergence {
+                port,
+                column: PostFrame::FrameIndex,
+                frame_index: post_start(a) + overlap as i32,
+                difference: missing as f32,
+                max_difference: missing as f32,
+                frame_count: missing,
+            });
+        }
+
+        if let Some(first) = divs.iter().map(|x| x.frame_index).min() {
+            if result.first_divergence.is_none_or(|x| first < x) {
+                result.first_divergence = Some(first);
+            }
+        }
+
+        result.divergences.extend(divs);
+    }
+
+    if let Some(frame) = result.first_divergence {
+        // frame indexes are identical between the 2 replays, so the offset is the same for both
+        let i = (frame - post_start(a)) as usize;
+        for player_a in &a.players {
+            let player_b = b.player_by_port(player_a.port)?;
+            // the shorter copy has no row to report if it diverged by ending early
+            if i >= player_a.frames.post.len() || i >= player_b.frames.post.len() {
+                continue;
+            }
+            result.first_rows.push((
+                player_a.port,
+                player_a.frames.post.get_frame(i),
+                player_b.frames.post.get_frame(i),
+            ));
+        }
+    }
+
+    result.divergences.sort_by_key(|x| x.frame_index);
+
+    Ok(result)
+}
+
+fn post_start(game: &Game) -> i32 {
+    game.players[0]
+        .frames
+        .post
+        .frame_index
+        .first()
+        .copied()
+        .unwrap_or(-123)
+}
@@ -43,7 +43,7 @@ pub enum PlayerType {
     Empty = 3,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, FromRepr, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, FromRepr, Default, Display, IntoStaticStr)]
 #[repr(u8)]
 pub enum ControllerFix {
     Off = 0,
@@ -191,6 +191,7 @@ impl GameStart {
                         combos: Default::default(),
                         frames: Default::default(),
                         nana_frames: None,
+                        transformations: Vec::new(),
                         costume: temp_players[i].2,
                         ucf: temp_ucf[i],
                     };
@@ -226,6 +227,7 @@ impl GameStart {
                         combos: Default::default(),
                         frames: Default::default(),
                         nana_frames: None,
+                        transformations: Vec::new(),
                         costume: temp_players[i].2,
                         ucf: temp_ucf[i],
                     };
@@ -254,6 +256,7 @@ impl GameStart {
                         combos: Default::default(),
                         frames: Default::default(),
                         nana_frames: None,
+                        transformations: Vec::new(),
                         costume: temp_players[i].2,
                         ucf: temp_ucf[i],
                     };
@@ -282,6 +285,7 @@ impl GameStart {
                         combos: Default::default(),
                         frames: Default::default(),
                         nana_frames: None,
+                        transformations: Vec::new(),
                         costume: temp_players[i].2,
                         ucf: temp_ucf[i],
                     };
@@ -310,6 +314,7 @@ impl GameStart {
                         combos: Default::default(),
                         frames: Default::default(),
                         nana_frames: None,
+                        transformations: Vec::new(),
                         costume: temp_players[i].2,
                         ucf: temp_ucf[i],
                     };
@@ -339,6 +344,7 @@ impl GameStart {
                         combos: Default::default(),
                         frames: Default::default(),
                         nana_frames: None,
+                        transformations: Vec::new(),
                         costume: temp_players[i].2,
                         ucf: temp_ucf[i],
                     };
@@ -385,6 +391,7 @@ impl GameStart {
                         combos: Default::default(),
                         frames: Default::default(),
                         nana_frames: None,
+                        transformations: Vec::new(),
                         costume: temp_players[i].2,
                         ucf: temp_ucf[i],
                     };
@@ -423,6 +430,7 @@ impl GameStart {
                         combos: Default::default(),
                         frames: Default::default(),
                         nana_frames: None,
+                        transformations: Vec::new(),
                         costume: temp_players[i].2,
                         ucf: temp_ucf[i],
                     };
@@ -451,6 +459,7 @@ impl GameStart {
                         combos: Default::default(),
                         frames: Default::default(),
                         nana_frames: None,
+                        transformations: Vec::new(),
                         costume: temp_players[i].2,
                         ucf: temp_ucf[i],
                     };
@@ -493,6 +502,7 @@ impl GameStart {
                     combos: Default::default(),
                     frames: Default::default(),
                     nana_frames: None,
+                    transformations: Vec::new(),
                     costume: temp_players[i].2,
                     ucf: temp_ucf[i],
                 };
@@ -0,0 +1,222 @@
+use bytes::{Buf, Bytes};
+use ssbm_utils::enums::stage::{GroundID, Stage, StageID};
+use strum_macros::{Display, EnumString, FromRepr, IntoStaticStr};
+
+/// Height of each Fountain of Dreams side platform at the start of the match, before any platform
+/// events have fired
+pub const FOD_LEFT_PLATFORM_START: f32 = 20.0;
+pub const FOD_RIGHT_PLATFORM_START: f32 = 28.0;
+/// Max vertical distance between a character and the live height of the Fountain of Dreams side
+/// platform their ground id points to, for them to be considered standing on it
+pub const FOD_PLATFORM_TOLERANCE: f32 = 1.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, FromRepr, EnumString, Display, IntoStaticStr)]
+#[repr(u8)]
+pub enum FodPlatform {
+    RIGHT = 0,
+    LEFT = 1,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, FromRepr, EnumString, Display, IntoStaticStr)]
+#[repr(u8)]
+pub enum WhispyDirection {
+    NONE = 0,
+    LEFT = 1,
+    RIGHT = 2,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, FromRepr, EnumString, Display, IntoStaticStr)]
+#[repr(u16)]
+pub enum StadiumTransformation {
+    FIRE = 3,
+    GRASS = 4,
+    NORMAL = 5,
+    ROCK = 6,
+    WATER = 9,
+}
+
+/// Stage-specific events. Only stages with moving parts produce these events, so 2 of the 3
+/// containers will always be empty.
+///
+/// Events on rolled back frames are overwritten by their re-simulated counterparts, so each
+/// container only contains finalized events.
+///
+/// added v3.18.0
+#[derive(Debug, Default, Clone)]
+pub struct StageEvents {
+    pub fod_platforms: FodPlatforms,
+    pub whispy: WhispyBlows,
+    pub stadium_transformations: StadiumTransformations,
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct FodPlatforms {
+    pub frame_index: Vec<i32>,
+    pub platform: Vec<FodPlatform>,
+    pub height: Vec<f32>,
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct WhispyBlows {
+    pub frame_index: Vec<i32>,
+    pub direction: Vec<WhispyDirection>,
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct StadiumTransformations {
+    pub frame_index: Vec<i32>,
+    /// Raw transformation event ID (e.g. stage beginning to change, background finishing, etc.)
+    pub event: Vec<u16>,
+    pub transformation: Vec<Option<StadiumTransformation>>,
+}
+
+/// Finds the first index whose frame is >= `frame`, so rolled back events can be truncated
+fn rollback_point(frame_index: &[i32], frame: i32) -> usize {
+    frame_index.partition_point(|&x| x < frame)
+}
+
+impl StageEvents {
+    /// Returns the height of the given Fountain of Dreams side platform on the given melee frame.
+    /// Returns None for any other ground, or if the stage isn't Fountain of Dreams. Other stages'
+    /// side platforms share the same ground ids, but never move
+    pub fn platform_height(
+        &self,
+        stage: &Stage,
+        frame_index: i32,
+        ground: GroundID,
+    ) -> Option<f32> {
+        if stage.id != StageID::FOUNTAIN_OF_DREAMS {
+            return None;
+        }
+
+        let (platform, start) = match ground {
+            GroundID::LEFT_PLATFORM => (FodPlatform::LEFT, FOD_LEFT_PLATFORM_START),
+            GroundID::RIGHT_PLATFORM => (FodPlatform::RIGHT, FOD_RIGHT_PLATFORM_START),
+            _ => return None,
+        };
+
+        let plats = &self.fod_platforms;
+        let end = plats.frame_index.partition_point(|&x| x <= frame_index);
+
+        Some(
+            (0..end)
+                .rev()
+                .find(|&i| plats.platform[i] == platform)
+                .map_or(start, |i| plats.height[i]),
+        )
+    }
+
+    /// Resolves the ground a character at height `y` is on, using the live height of Fountain of
+    /// Dreams' side platforms. A ground id that points to a side platform the character isn't level
+    /// with is stale (e.g. the platform sank out from under them), so it resolves to
+    /// `GroundID::UNKNOWN`. Identical to `Stage::ground_from_id` on every other stage
+    pub fn ground_at(&self, stage: &Stage, frame_index: i32, ground_id: u16, y: f32) -> GroundID {
+        let ground = stage.ground_from_id(ground_id);
+
+        match self.platform_height(stage, frame_index, ground) {
+            Some(height) if (y - height).abs() > FOD_PLATFORM_TOLERANCE => GroundID::UNKNOWN,
+            _ => ground,
+        }
+    }
+
+    /// Returns the direction whispy is blowing on the given melee frame
+    pub fn whispy_direction(&self, frame_index: i32) -> WhispyDirection {
+        let end = self
+            .whispy
+            .frame_index
+            .partition_point(|&x| x <= frame_index);
+        if end == 0 {
+            return WhispyDirection::NONE;
+        }
+        self.whispy.direction[end - 1]
+    }
+
+    /// Returns the most recent transformation on the given melee frame. None if the stage hasn't
+    /// transformed yet
+    pub fn stadium_transformation(&self, frame_index: i32) -> Option<StadiumTransformation> {
+        let end = self
+            .stadium_transformations
+            .frame_index
+            .partition_point(|&x| x <= frame_index);
+        if end == 0 {
+            return None;
+        }
+        self.stadium_transformations.transformation[end - 1]
+    }
+}
+
+pub fn parse_stage_events(
+    stream: Bytes,
+    fod_offsets: &[usize],
+    whispy_offsets: &[usize],
+    stadium_offsets: &[usize],
+) -> StageEvents {
+    let mut working = StageEvents::default();
+
+    /* Rolled back frames re-fire their events. Anything on a later frame than the current event
+    can't have happened yet, so it's truncated. Events on the *same* frame are replaced if they
+    have the same key (platform/transformation event), since multiple events can legitimately fire
+    on the same frame */
+    for &offset in fod_offsets {
+        let mut event = stream.slice(offset..);
+        let frame = event.get_i32();
+        let platform = FodPlatform::from_repr(event.get_u8()).unwrap_or(FodPlatform::RIGHT);
+        let height = event.get_f32();
+
+        let plats = &mut working.fod_platforms;
+        let cut = rollback_point(&plats.frame_index, frame + 1);
+        plats.frame_index.truncate(cut);
+        plats.platform.truncate(cut);
+        plats.height.truncate(cut);
+
+        let start = rollback_point(&plats.frame_index, frame);
+        match (start..cut).find(|&i| plats.platform[i] == platform) {
+            Some(i) => plats.height[i] = height,
+            None => {
+                plats.frame_index.push(frame);
+                plats.platform.push(platform);
+                plats.height.push(height);
+            }
+        }
+    }
+
+    for &offset in whispy_offsets {
+        let mut event = stream.slice(offset..);
+        let frame = event.get_i32();
+        let direction = WhispyDirection::from_repr(event.get_u8()).unwrap_or(WhispyDirection::NONE);
+
+        // whispy can only blow 1 direction at a time, so there's at most 1 event per frame
+        let whispy = &mut working.whispy;
+        let cut = rollback_point(&whispy.frame_index, frame);
+        whispy.frame_index.truncate(cut);
+        whispy.direction.truncate(cut);
+
+        whispy.frame_index.push(frame);
+        whispy.direction.push(direction);
+    }
+
+    for &offset in stadium_offsets {
+        let mut event = stream.slice(offset..);
+        let frame = event.get_i32();
+        let event_id = event.get_u16();
+        let transformation = StadiumTransformation::from_repr(event.get_u16());
+
+        let stadium = &mut working.stadium_transformations;
+        let cut = rollback_point(&stadium.frame_index, frame + 1);
+        stadium.frame_index.truncate(cut);
+        stadium.event.truncate(cut);
+        stadium.transformation.truncate(cut);
+
+        let start = rollback_point(&stadium.frame_index, frame);
+        match (start..cut).find(|&i| stadium.event[i] == event_id) {
+            Some(i) => stadium.transformation[i] = transformation,
+            None => {
+                stadium.frame_index.push(frame);
+                stadium.event.push(event_id);
+                stadium.transformation.push(transformation);
+            }
+        }
+    }
+
+    working
+}
@@ -1,10 +1,17 @@
 use std::sync::Arc;
 
-use ssbm_utils::{enums::{ActionState, BitFlags, EngineInput, Flags}, checks::*};
+use ssbm_utils::{
+    calc::staled_damage,
+    checks::*,
+    enums::{ActionState, Attack, BitFlags, Character, EngineInput, Flags},
+};
 
-use crate::events::{
-    post_frame::{PostFrames, PostRow},
-    pre_frame::{PreFrames, PreRow},
+use crate::{
+    events::{
+        post_frame::{PostFrames, PostRow},
+        pre_frame::{PreFrames, PreRow},
+    },
+    stats::{hits::hit_by, powershield::shield_hit},
 };
 
 /// Container for Pre-frame and Post-frame containers.
@@ -18,6 +25,121 @@ use crate::events::{
 pub struct Frames {
     pub pre: Arc<PreFrames>,
     pub post: Arc<PostFrames>,
+    /// The player's stale move queue at the end of each frame, ordered from most recent to least
+    /// recent. Empty slots are `Attack::NONE`. See `build_stale_queue` for the rules used.
+    pub stale_queue: Arc<[StaleQueue]>,
+}
+
+pub const STALE_QUEUE_LEN: usize = 9;
+/// Max number of frames before a special starts to look for the B press that started it. The state
+/// change can be delayed from the press, e.g. when the input is buffered out of another action
+const SPECIAL_INPUT_LOOKBACK: usize = 4;
+
+pub type StaleQueue = [Attack; STALE_QUEUE_LEN];
+
+/// Reconstructs the player's stale move queue from the hits they landed on the opponent.
+///
+/// * A move enters the queue when it hits the opponent or their shield. Shield hits don't update
+///   `last_attack_landed`, so the attack is taken from the player's action state instead
+/// * For Ice Climbers, a hit only enters the queue of the climber it came from. Hits are attributed
+///   via the victim's `instance_hit_by`, and shield hits via which climber entered hitlag. Replays
+///   without instance ids (< 3.16.0) credit hits to both climbers
+/// * Each use of a move only enters the queue once, no matter how many times it hits. A new use
+///   starts whenever the player changes action states
+/// * `Attack::NONE` and `Attack::NON_STALING` never enter the queue
+/// * The queue is emptied when the player loses a stock
+pub(crate) fn build_stale_queue(
+    plyr_frames: &Frames,
+    opnt_frames: &Frames,
+    ice_climber: bool,
+) -> Arc<[StaleQueue]> {
+    let attacks = &plyr_frames.post.last_attack_landed;
+    let has_instance_ids = opnt_frames.post.instance_hit_by.is_some();
+    let len = plyr_frames.len().min(opnt_frames.len());
+
+    let mut result = Vec::with_capacity(len);
+    let mut queue = [Attack::NONE; STALE_QUEUE_LEN];
+    let mut entered = false;
+
+    if len > 0 {
+        result.push(queue);
+    }
+
+    for i in 1..len {
+        if plyr_frames.just_lost_stock(i) {
+            queue = [Attack::NONE; STALE_QUEUE_LEN];
+        }
+
+        if plyr_frames.just_changed_state(i) {
+            entered = false;
+        }
+
+        let attack = if opnt_frames.just_took_damage(i) {
+            let credited =
+                !ice_climber || !has_instance_ids || hit_by(opnt_frames, plyr_frames, i);
+            credited.then(|| Attack::from_repr(attacks[i])).flatten()
+        } else if shield_hit(opnt_frames, i) && (!ice_climber || plyr_frames.in_hitlag(i)) {
+            plyr_frames.current_attack(i)
+        } else {
+            None
+        };
+
+        if let Some(attack) =
+            attack.filter(|x| !entered && !matches!(x, Attack::NONE | Attack::NON_STALING))
+        {
+            queue.rotate_right(1);
+            queue[0] = attack;
+            entered = true;
+        }
+
+        result.push(queue);
+    }
+
+    result.into()
+}
+
+/// Returns the attack that an action state starts. Specials are character-specific, so they're
+/// handled separately in `Frames::special_started`
+pub(crate) fn state_to_attack(state: u16) -> Option<Attack> {
+    use ActionState as A;
+    let attack = match A::from_repr(state)? {
+        A::ATTACK_11 => Attack::JAB_1,
+        A::ATTACK_12 => Attack::JAB_2,
+        A::ATTACK_13 => Attack::JAB_3,
+        A::ATTACK_100_START => Attack::RAPID_JAB,
+        A::ATTACK_DASH => Attack::DASH_ATTACK,
+        A::ATTACK_S_3_HI
+        | A::ATTACK_S_3_HI_S
+        | A::ATTACK_S_3_S
+        | A::ATTACK_S_3_LW_S
+        | A::ATTACK_S_3_LW => Attack::F_TILT,
+        A::ATTACK_HI_3 => Attack::U_TILT,
+        A::ATTACK_LW_3 => Attack::D_TILT,
+        A::ATTACK_S_4_HI
+        | A::ATTACK_S_4_HI_S
+        | A::ATTACK_S_4_S
+        | A::ATTACK_S_4_LW_S
+        | A::ATTACK_S_4_LW => Attack::F_SMASH,
+        A::ATTACK_HI_4 => Attack::U_SMASH,
+        A::ATTACK_LW_4 => Attack::D_SMASH,
+        A::ATTACK_AIR_N => Attack::NAIR,
+        A::ATTACK_AIR_F => Attack::FAIR,
+        A::ATTACK_AIR_B => Attack::BAIR,
+        A::ATTACK_AIR_HI => Attack::UAIR,
+        A::ATTACK_AIR_LW => Attack::DAIR,
+        A::DOWN_ATTACK_U => Attack::GET_UP_ATTACK_BACK,
+        A::DOWN_ATTACK_D => Attack::GET_UP_ATTACK_FRONT,
+        A::CATCH_ATTACK => Attack::PUMMEL,
+        A::THROW_F => Attack::FORWARD_THROW,
+        A::THROW_B => Attack::BACK_THROW,
+        A::THROW_HI => Attack::UP_THROW,
+        A::THROW_LW => Attack::DOWN_THROW,
+        A::CLIFF_ATTACK_SLOW => Attack::LEDGE_ATTACK_SLOW,
+        A::CLIFF_ATTACK_QUICK => Attack::LEDGE_ATTACK_FAST,
+        _ => return None,
+    };
+
+    Some(attack)
 }
 
 impl Frames {
@@ -37,6 +159,88 @@ impl Frames {
         Frame(self.pre.get_frame(index), self.post.get_frame(index))
     }
 
+    /// Returns the character active on the given frame (0-indexed), which can differ from
+    /// `Player::character` after a Zelda/Sheik transformation. Popo and Nana are both reported as
+    /// `Character::IceClimbers` to match `Player::character`. Returns None for frames where Nana is
+    /// inactive
+    pub fn character(&self, i: usize) -> Option<Character> {
+        match Character::try_from_internal(self.post.character[i]).ok()? {
+            Character::Popo | Character::Nana => Some(Character::IceClimbers),
+            c => Some(c),
+        }
+    }
+
+    /// Returns the special move started on this frame, based on the joystick direction when B was
+    /// pressed (up to `SPECIAL_INPUT_LOOKBACK` frames earlier). Only counts entries into
+    /// character-specific states from generic states, so multi-state specials (e.g. shine -> shine
+    /// loop) are counted once
+    pub fn special_started(&self, i: usize) -> Option<Attack> {
+        let states = &self.post.action_state;
+        let special = |k: usize| states[k] > ActionState::BARREL_CANNON_WAIT as u16;
+
+        if i == 0 || !special(i) || special(i - 1) {
+            return None;
+        }
+
+        let press = (i.saturating_sub(SPECIAL_INPUT_LOOKBACK).max(1)..=i)
+            .rev()
+            .find(|&k| self.just_pressed_any(EngineInput::B, k))?;
+
+        let stick = self.pre.joystick[press];
+        let attack = if stick.y.abs() >= 0.2875 && stick.y.abs() >= stick.x.abs() {
+            if stick.y > 0.0 {
+                Attack::UP_SPECIAL
+            } else {
+                Attack::DOWN_SPECIAL
+            }
+        } else if stick.x.abs() >= 0.2875 {
+            Attack::SIDE_SPECIAL
+        } else {
+            Attack::NEUTRAL_SPECIAL
+        };
+
+        Some(attack)
+    }
+
+    /// Returns the attack the player is performing on the given frame (0-indexed), based on their
+    /// action state. Unlike `post.last_attack_landed`, this is accurate on frames where the attack
+    /// hits a shield. Returns None if the player isn't attacking, e.g. a projectile that connects
+    /// after they've moved on
+    pub fn current_attack(&self, i: usize) -> Option<Attack> {
+        let states = &self.post.action_state;
+        let special = |k: usize| states[k] > ActionState::BARREL_CANNON_WAIT as u16;
+
+        if let Some(attack) = state_to_attack(states[i]) {
+            return Some(attack);
+        }
+
+        if !special(i) {
+            return None;
+        }
+
+        // specials can span several character-specific states, so the direction is taken from
+        // the frame the player entered the first one
+        let start = (0..=i).rev().take_while(|&k| special(k)).last()?;
+        self.special_started(start)
+    }
+
+    /// Returns the stale queue slots occupied by `attack` as they were when a hit landed on frame
+    /// `i` (i.e. not including that hit), in the format expected by `calc::staled_damage`
+    pub fn stale_slots(&self, attack: Attack, i: usize) -> [bool; STALE_QUEUE_LEN] {
+        let queue = self
+            .stale_queue
+            .get(i.saturating_sub(1))
+            .copied()
+            .unwrap_or_default();
+        queue.map(|x| x != Attack::NONE && x == attack)
+    }
+
+    /// Fraction of `attack`'s damage removed by staling for a hit that landed on frame `i`. 0.0 is
+    /// fully fresh, the maximum is 0.45
+    pub fn staleness(&self, attack: Attack, i: usize) -> f32 {
+        1.0 - staled_damage(1.0, &self.stale_slots(attack, i))
+    }
+
     pub fn get_last_frame(&self) -> Frame {
         Frame(
             self.pre.get_frame(self.len() - 1),
@@ -78,12 +282,19 @@ impl Frames {
 
     #[inline]
     pub fn just_took_damage(&self, i: usize) -> bool {
-        i > 0 && just_took_damage(self.post.percent[i], self.post.percent[i - 1])
+        // nana's percent is -1 while she's inactive, so her respawn shouldn't count as damage
+        i > 0
+            && self.post.percent[i - 1] >= 0.0
+            && just_took_damage(self.post.percent[i], self.post.percent[i - 1])
     }
 
     #[inline]
     pub fn damage_taken(&self, i: usize) -> f32 {
-        (self.post.percent[i] - self.post.percent.get(i - 1).unwrap_or(&0.0)).max(0.0)
+        let prev = self.post.percent.get(i - 1).unwrap_or(&0.0);
+        if *prev < 0.0 {
+            return 0.0;
+        }
+        (self.post.percent[i] - prev).max(0.0)
     }
 
     #[inline]
@@ -6,6 +6,7 @@ use std::{
 };
 
 use anyhow::{anyhow, ensure, Result};
+use bytes::Bytes;
 use itertools::Itertools;
 use time::OffsetDateTime;
 
@@ -16,11 +17,17 @@ use crate::{
         game_end::{EndMethod, GameEnd, Placement},
         game_start::{GameStart, MatchType, Version},
         item_frames::ItemFrames,
+        stage_events::StageEvents,
     },
     player::{Player, PlayerStub},
     stats::{
-        combos::find_combos, defense::find_defense, inputs::find_inputs, items::find_items,
-        lcancel::find_lcancels, tech::find_techs, wavedash::find_wavedashes, Stats,
+        combos::find_combos, controller::find_controller, conversions::find_conversions,
+        defense::find_defense, edgeguard::find_edgeguards, grabs::find_grabs, hits::find_hits,
+        ics::find_ics, inputs::find_inputs, items::find_items, kills::find_kills,
+        lcancel::find_lcancels, ledge::find_ledge, movement::find_movement, moves::find_moves,
+        powershield::find_powershields, recovery::find_recovery, shield::find_shield,
+        tech::find_techs, wavedash::find_wavedashes, ComboConfig, Combos, ConversionStats, Stats,
+        CONVERSION_RESET_WINDOW,
     },
 };
 
@@ -117,6 +124,24 @@ pub trait GameMetadata {
         self.metadata().end.as_ref().and_then(|x| x.lras_initiator)
     }
 
+    /// True if the game was ended via LRAS. LRAS initiators are only recorded as of v2.0.0
+    #[inline]
+    fn ended_by_lras(&self) -> bool {
+        self.end_method() == Some(EndMethod::NoContest) && self.version().at_least(2, 0, 0)
+    }
+
+    /// Anyone who LRAS's loses by default (matches slippi behavior), so the winner is whichever of
+    /// the given ports *didn't* LRAS. None if the game wasn't ended via LRAS, or the initiator is
+    /// unknown
+    fn lras_winner(&self, ports: [Port; 2]) -> Option<Port> {
+        if !self.ended_by_lras() {
+            return None;
+        }
+
+        let loser = self.lras_initiator()?;
+        ports.into_iter().find(|&x| x != loser)
+    }
+
     #[inline]
     fn placements(&self) -> Option<&HashMap<Port, Placement>> {
         self.metadata()
@@ -141,7 +166,7 @@ pub trait GameMetadata {
     }
 }
 
-#[derive(Debug, Clone, PartialEq,)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct Metadata {
     /// Replay SemVer number in the form `major`, `minor`, `revision`
     pub version: Version,
@@ -196,6 +221,14 @@ pub struct Game {
     /// items spawned during the match (highly unlikely), but the container will populate so long as
     /// the replay is new enough
     pub item_frames: Option<Arc<ItemFrames>>,
+    /// Contains stage-specific events (Fountain of Dreams platforms, Pokemon Stadium
+    /// transformations, Dreamland wind) if the replay is new enough (>= v3.18.0). The container
+    /// will still populate on stages without any events.
+    pub stage_events: Option<Arc<StageEvents>>,
+    /// Raw `(command byte, payload)` pairs for any events declared in the replay that this parser
+    /// doesn't recognize. Usually empty, but allows newer replays to parse before the parser is
+    /// updated.
+    pub unknown_events: Vec<(u8, Bytes)>,
 }
 
 impl Game {
@@ -278,6 +311,7 @@ impl Game {
                 combos: player.combos.clone(),
                 frames: Default::default(),
                 nana_frames: player.nana_frames.clone(),
+                transformations: player.transformations.clone(),
             }));
         }
 
@@ -295,18 +329,109 @@ impl Game {
             let player = players[0];
             let opponent = players[1];
             let items = &self.item_frames;
+            let stage_events = self.stage_events.as_deref();
+
+            // requires only states and percents thus has no version requirement
+            let conversions =
+                find_conversions(&player.frames, &opponent.frames, CONVERSION_RESET_WINDOW);
 
-            // inputs are available in every replay version
-            let input = find_inputs(&player.frames, self.total_frames());
+            // inputs are available in every replay version. Conversions are used to split inputs
+            // by game phase
+            let input = find_inputs(&player.frames, &conversions, self.total_frames());
 
             // l cancel status was with 2.0.0 on 3/19/2019
             let l_cancel = version
                 .at_least(2, 0, 0)
-                .then(|| find_lcancels(&player.frames, &stage));
+                .then(|| find_lcancels(&player.frames, &stage, stage_events));
+
+            let player_nana = player.nana_frames.as_ref();
+            let opnt_nana = opponent.nana_frames.as_ref();
+
+            let tech = version.at_least(2, 0, 0).then(|| {
+                find_techs(
+                    &player.frames,
+                    &opponent.frames,
+                    opnt_nana,
+                    &stage,
+                    stage_events,
+                )
+            });
+
+            let nana_tech = player_nana
+                .filter(|_| version.at_least(2, 0, 0))
+                .map(|nana| find_techs(nana, &opponent.frames, opnt_nana, &stage, stage_events));
+
+            // requires hitstun flags and jumps remaining, both of which were added in 2.0.0
+            let recovery = version
+                .at_least(2, 0, 0)
+                .then(|| find_recovery(&player.frames, &opponent.frames, &stage));
+
+            // projectiles are only detected if item owners are available (>= 3.6.0)
+            let edgeguard = version.at_least(2, 0, 0).then(|| {
+                find_edgeguards(
+                    &player.frames,
+                    &opponent.frames,
+                    &stage,
+                    player.port,
+                    items.as_deref(),
+                )
+            });
+
+            let kills = version
+                .at_least(2, 0, 0)
+                .then(|| find_kills(&player.frames, &opponent.frames, opnt_nana, &stage));
+
+            // nana's stock counter drops to 0 while she's inactive, so her deaths are detected the
+            // same way as popo's
+            let nana_kills = player_nana
+                .filter(|_| version.at_least(2, 0, 0))
+                .map(|nana| find_kills(nana, &opponent.frames, opnt_nana, &stage));
+
+            // nana's inputs are available in every replay version
+            let ics = player_nana.map(|nana| find_ics(&player.frames, nana));
+
+            // requires hitlag flags, which were added in 2.0.0
+            let shield = version
+                .at_least(2, 0, 0)
+                .then(|| find_shield(&player.frames, &opponent.frames, player.character));
+
+            let powershield = version.at_least(2, 0, 0).then(|| {
+                find_powershields(
+                    &player.frames,
+                    &opponent.frames,
+                    player.port,
+                    items.as_deref(),
+                )
+            });
+
+            // follow-ups rely on hitstun flags
+            let grab = version
+                .at_least(2, 0, 0)
+                .then(|| find_grabs(&player.frames, &opponent.frames));
 
-            let tech = version
+            // shield hits are detected via hitlag flags
+            let moves = version
                 .at_least(2, 0, 0)
-                .then(|| find_techs(&player.frames, &opponent.frames, &stage));
+                .then(|| find_moves(&player.frames, &opponent.frames));
+
+            // attribution relies on instance ids, which were added in v3.16.0
+            let hits = version
+                .at_least(3, 16, 0)
+                .then(|| find_hits(player, opponent, items.as_deref()));
+
+            // failed shield drops are detected via the ground id, which was added in 2.0.0
+            let movement = version
+                .at_least(2, 0, 0)
+                .then(|| find_movement(&player.frames, &stage, player.ucf));
+
+            let ledge = version
+                .at_least(2, 0, 0)
+                .then(|| find_ledge(&player.frames, &stage));
+
+            // raw stick y was added in 3.15.0
+            let controller = version
+                .at_least(3, 15, 0)
+                .then(|| find_controller(&player.frames));
 
             // requires fields up to item.owner which was released just after rollback on 7/8/2020
             let item = version
@@ -319,14 +444,28 @@ impl Game {
                 find_defense(
                     &player.frames,
                     &opponent.frames,
+                    opnt_nana,
                     self.metadata.start.stage as u16,
                     player.character,
                     opponent.character,
                 )
             });
 
+            let nana_defense = player_nana
+                .filter(|_| version.at_least(3, 5, 0))
+                .map(|nana| {
+                    find_defense(
+                        nana,
+                        &opponent.frames,
+                        opnt_nana,
+                        self.metadata.start.stage as u16,
+                        player.character,
+                        opponent.character,
+                    )
+                });
+
             // requires inputs and states thus has no version requirement
-            let wavedash = find_wavedashes(&player.frames);
+            let wavedash = find_wavedashes(&player.frames, &stage);
 
             let stats = Arc::new(Stats {
                 metadata: self.metadata.clone(),
@@ -336,14 +475,33 @@ impl Game {
                 defense,
                 wavedash,
                 tech,
+                recovery,
+                edgeguard,
+                ledge,
+                conversions,
+                kills,
+                shield,
+                powershield,
+                grab,
+                moves,
+                hits,
+                nana_defense,
+                nana_tech,
+                nana_kills,
+                ics,
+                movement,
+                controller,
             });
 
             let combos = Arc::new(find_combos(
                 &player.frames,
+                player_nana,
                 &opponent.frames,
+                items.as_deref(),
                 self.metadata.start.stage,
                 player.character,
                 self.path().clone(),
+                &ComboConfig::default(),
             ));
 
             /* This should be a pretty cheap clone all things considered. The frames are 2 Arc
@@ -370,6 +528,7 @@ impl Game {
                 combos,
                 frames: player.frames.clone(),
                 nana_frames: player.nana_frames.clone(),
+                transformations: player.transformations.clone(),
             }));
         }
 
@@ -377,9 +536,44 @@ impl Game {
         self.players = result.try_into().unwrap();
     }
 
-    // pub fn get_combos(&mut self) {
-    //     find_combos(plyr_frames, opnt_frames, stage_id, player_char)
-    // }
+    /// Recalculates the given player's conversions using a custom reset window. The default window
+    /// (used by `Stats::conversions`) is `CONVERSION_RESET_WINDOW`
+    pub fn conversions_with(&self, port: Port, reset_window: u32) -> Result<ConversionStats> {
+        let player = self.player_by_port(port)?;
+        let opponent = self
+            .players
+            .iter()
+            .find(|x| x.port != port)
+            .ok_or_else(|| anyhow!("Unable to find opponent of port {port}"))?;
+
+        Ok(find_conversions(
+            &player.frames,
+            &opponent.frames,
+            reset_window,
+        ))
+    }
+
+    /// Recalculates the given player's combos using a custom `ComboConfig`. The default config
+    /// (used by `Player::combos`) matches the official parser
+    pub fn combos_with(&self, port: Port, config: &ComboConfig) -> Result<Combos> {
+        let player = self.player_by_port(port)?;
+        let opponent = self
+            .players
+            .iter()
+            .find(|x| x.port != port)
+            .ok_or_else(|| anyhow!("Unable to find opponent of port {port}"))?;
+
+        Ok(find_combos(
+            &player.frames,
+            player.nana_frames.as_ref(),
+            &opponent.frames,
+            self.item_frames.as_deref(),
+            self.metadata.start.stage,
+            player.character,
+            self.path().clone(),
+            config,
+        ))
+    }
 
     /// Returns the winner of the match if one can be decided conclusively
     pub fn winner(&self) -> Option<Port> {
@@ -392,9 +586,8 @@ impl Game {
         let p1 = &self.players[0];
         let p2 = &self.players[1];
 
-        // Anyone who LRAS's loses by default (matches slippi behavior)
-        if self.end_method() == Some(EndMethod::NoContest) && self.version().at_least(2, 0, 0) {
-            return self.lras_initiator();
+        if self.ended_by_lras() {
+            return self.lras_winner([p1.port, p2.port]);
         }
 
         let p1_stocks = *p1.frames.post.stocks.last().unwrap();
@@ -6,13 +6,16 @@ pub mod events {
     pub mod item_frames;
     pub mod post_frame;
     pub mod pre_frame;
+    pub mod stage_events;
 }
 
 pub mod columns;
+pub mod dedup;
 pub mod frames;
 pub mod game;
 pub mod parse;
 pub mod player;
+pub mod sets;
 pub mod stats;
 pub(crate) mod ubjson;
 pub mod utils;
@@ -20,7 +23,9 @@ pub mod utils;
 #[cfg(feature = "polars")]
 pub mod polars_impl;
 
+pub use crate::dedup::{compare_replays, dedup, GameId};
 pub use crate::game::{Game, GameMetadata, GameStub};
+pub use crate::sets::{group_sets, Set};
 pub use crate::stats::{
     Combos, DefenseStats, InputStats, ItemStats, LCancelStats, Stats, TechStats, WavedashStats,
 };
@@ -210,13 +215,16 @@ pub fn parse_stubs(path: &str, multithreaded: bool) -> Vec<GameStub> {
     panic!("invalid file path: {f_path:?}")
 }
 
-/// Accepts a string file path to a single replay, or a directory containing replays. Returns a HashMap containing the
-/// resultant game object(s). The hashmap keys are the match id, so any replays older than 3.14.0 will be filtered out. Iterating over the keys will return them in order
+/// Accepts a string file path to a single replay, or a directory containing replays. Returns a vector containing the
+/// resultant game object(s) grouped into sets. Sorted by oldest -> newest.
+///
+/// Replays 3.14.0 and newer are grouped by match id. Older replays are grouped heuristically by player pairing and
+/// the time between games, see `sets::group_sets`
 ///
 /// Replays that error out during parsing for any reason are skipped
-// pub fn parse_sets(path: &str, multithreaded: bool) -> BTreeMap<_, Game> {
-
-// }
+pub fn parse_sets(path: &str, stats: bool, multithreaded: bool) -> Vec<Set<Game>> {
+    group_sets(parse(path, stats, multithreaded))
+}
 
 /// Returns a single stats object containing the stats from all individual games.
 pub fn get_stats(games: &[Game], connect_code: &str) -> Vec<Arc<Stats>> {
@@ -267,11 +275,13 @@ pub fn to_dolphin_queue(target_path: PathBuf, combo_list: &[&stats::combos::Comb
 
 pub mod prelude {
     pub use crate::{
+        dedup::{compare_replays, dedup, GameId, Identity, SyncStatus},
         game::{Game, GameMetadata, GameStub},
         player::Player,
+        sets::{group_sets, Set, SetEntry},
         stats::*,
     };
-    pub use crate::{get_combos, get_stats, parse, to_dolphin_queue};
+    pub use crate::{get_combos, get_stats, parse, parse_sets, to_dolphin_queue};
     pub use strum::VariantNames;
 
     // pub use ssbm_utils;
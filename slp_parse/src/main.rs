@@ -6,7 +6,7 @@ use itertools::izip;
 use ssbm_utils::prelude::{Character, Flags};
 use std::{collections::HashSet, time::Instant};
 
-use slp_parse::{prelude::*, stats::{rate_falco_combos, StatType}};
+use slp_parse::{prelude::*, stats::{ComboScorer, StatType}};
 // static REPLAY: &[u8; 165123] = include_bytes!(r"G:/temp\Game_20230627T174002.slp");
 
 macro_rules! timeit {
@@ -43,7 +43,7 @@ pub fn main() {
             dbg!(games.players[0].frames.get_frame(i).1);
         }
     }
-    // let mut rated = rate_falco_combos("NUT#356", &games);
+    // let mut rated = ComboScorer::new().rate_combos("NUT#356", &games);
     // rated.sort_by(|a, b| b.1.cmp(&a.1));
     // // for a in &rated {
     // //     println!("start: {}, end: {}, rating: {}", a.0.start_frame, a.0.end_frame, a.1);
@@ -18,9 +18,10 @@ use crate::game::{GameStub, Metadata};
 use crate::{
     events::{
         game_end::parse_gameend, game_start::GameStart, item_frames::parse_itemframes,
-        post_frame::parse_postframes, pre_frame::parse_preframes,
+        post_frame::parse_postframes, pre_frame::parse_preframes, stage_events::parse_stage_events,
     },
-    frames::Frames,
+    frames::{build_stale_queue, Frames},
+    player::find_transformations,
     ubjson,
     utils::ParseError,
     Game,
@@ -50,6 +51,9 @@ enum EventType {
     Item = 0x3B,
     FrameEnd = 0x3C,
     GeckoList = 0x3D,
+    FodPlatform = 0x3F,
+    Whispy = 0x40,
+    StadiumTransformation = 0x41,
     MessageSplitter = 0x10,
     #[default]
     None = 0x00,
@@ -80,13 +84,15 @@ impl Game {
         Ok(Bytes::from(file_data))
     }
 
-    fn get_event_sizes(file: &mut Bytes) -> Result<HashMap<EventType, u16>> {
-        let code = EventType::from_repr(file.get_u8()).unwrap();
+    /// Event sizes are keyed by the raw command byte rather than `EventType` so that events this
+    /// parser doesn't know about can still be skipped
+    fn get_event_sizes(file: &mut Bytes) -> Result<HashMap<u8, u16>> {
+        let code = file.get_u8();
         ensure!(
-            code == EventType::EventPayloads,
+            code == EventType::EventPayloads as u8,
             ParseError::Value(
                 format!("{:?}", EventType::EventPayloads),
-                format!("{:?}", code)
+                format!("{:#X}", code)
             )
         );
 
@@ -100,7 +106,7 @@ impl Game {
         let mut event_map = HashMap::default();
 
         for _ in (0..(payloads_size - 1)).step_by(3) {
-            let event = EventType::from_repr(file.get_u8()).unwrap();
+            let event = file.get_u8();
             let size = file.get_u16();
             event_map.insert(event, size);
         }
@@ -193,8 +199,12 @@ impl Game {
 
         expect_bytes(&mut stream, &[EventType::GameStart as u8])?;
 
-        let raw_start = stream.slice(0..event_sizes[&EventType::GameStart] as usize);
-        stream.advance(event_sizes[&EventType::GameStart] as usize);
+        let start_size = *event_sizes
+            .get(&(EventType::GameStart as u8))
+            .ok_or_else(|| anyhow!("GameStart missing from EventPayloads"))?
+            as usize;
+        let raw_start = stream.slice(0..start_size);
+        stream.advance(start_size);
 
         // .slice(
         //     // wow this is exceptionally ugly! thanks rust =)
@@ -237,28 +247,37 @@ impl Game {
         let mut pre_offsets = Vec::with_capacity(frame_count * (3 + ics_count));
         let mut post_offsets = Vec::with_capacity(frame_count * (3 + ics_count));
         let mut item_offsets = Vec::new();
+        let mut fod_offsets = Vec::new();
+        let mut whispy_offsets = Vec::new();
+        let mut stadium_offsets = Vec::new();
+        let mut unknown_events = Vec::new();
 
         let mut pos = file_data.len() - stream.len();
 
         while pos < raw_length as usize && event != EventType::GameEnd {
             let code = stream.get_u8();
+            /* Any event declared in EventPayloads can be skipped, even if this parser doesn't know
+            what it is. A code that *isn't* declared means we can't know how far to advance, which
+            is most likely a misalignment of my slices */
+            let size = *event_sizes.get(&code).ok_or_else(|| {
+                anyhow!("Event code {code:#X} at offset {pos} not declared in EventPayloads")
+            })? as usize;
+            // EventType::None allows the parser to continue working on newer replays (with
+            // possible new events)
             event = EventType::from_repr(code).unwrap_or_default();
-            /* EventType::None allows the parser to continue working on newer replays (with possible
-            new events). During testing all events must be accounted for, so any EventType::Nones
-            are likely a misalignment of my slices */
-            debug_assert!(event != EventType::None);
 
             match event {
                 EventType::PreFrame => pre_offsets.push(pos + 1),
                 EventType::PostFrame => post_offsets.push(pos + 1),
                 EventType::Item => item_offsets.push(pos + 1),
-                EventType::GameEnd => {
-                    let size = event_sizes[&event] as usize;
-                    game_end_bytes = Some(stream.slice(..size))
-                }
+                EventType::FodPlatform => fod_offsets.push(pos + 1),
+                EventType::Whispy => whispy_offsets.push(pos + 1),
+                EventType::StadiumTransformation => stadium_offsets.push(pos + 1),
+                EventType::GameEnd => game_end_bytes = Some(stream.slice(..size)),
+                EventType::None => unknown_events.push((code, stream.slice(..size))),
                 _ => (),
             }
-            stream.advance(event_sizes[&event] as usize);
+            stream.advance(size);
             pos = file_data.len() - stream.len();
         }
 
@@ -286,6 +305,16 @@ impl Game {
                 item_frames = Some(parse_itemframes(file_data.clone(), metadata.clone(), &item_offsets));
         }
 
+        let mut stage_events = None;
+        if version.at_least(3, 18, 0) {
+            stage_events = Some(parse_stage_events(
+                file_data.clone(),
+                &fod_offsets,
+                &whispy_offsets,
+                &stadium_offsets,
+            ));
+        }
+
 
 
         let (pre_frames, post_frames) = rayon::join(
@@ -319,18 +348,40 @@ impl Game {
 
             let temp_post = post_f.remove(&(player.port as u8)).unwrap();
             player.frames.post = Arc::new(temp_post.0);
+            player.transformations = find_transformations(&player.frames, player.character);
             if temp_pre.1.is_some() {
                 player.nana_frames = Some(Frames {
                     pre: Arc::new(temp_pre.1.unwrap()),
                     post: Arc::new(temp_post.1.unwrap()),
+                    ..Default::default()
                 })
             }
         }
 
+        // the stale queue depends on hits landed on the opponent, so it can only be built once
+        // both players' frames are available
+        let stale_queues = [0, 1].map(|i| {
+            let opnt_frames = &players[1 - i].frames;
+            let nana_frames = players[i].nana_frames.as_ref();
+            (
+                build_stale_queue(&players[i].frames, opnt_frames, nana_frames.is_some()),
+                nana_frames.map(|nana| build_stale_queue(nana, opnt_frames, true)),
+            )
+        });
+        for (player, (stale_queue, nana_queue)) in players.iter_mut().zip(stale_queues) {
+            player.frames.stale_queue = stale_queue;
+            if let (Some(nana_frames), Some(nana_queue)) = (player.nana_frames.as_mut(), nana_queue)
+            {
+                nana_frames.stale_queue = nana_queue;
+            }
+        }
+
         Ok(Game {
             metadata,
             players: players.map(Arc::new),
             item_frames: item_frames.map(Arc::new),
+            stage_events: stage_events.map(Arc::new),
+            unknown_events,
         })
     }
 
@@ -358,11 +409,14 @@ impl Game {
         let payloads_size = stream.read_u8().unwrap();
 
         let mut start_len = 0;
+        let mut end_len = None;
         for _ in (0..(payloads_size - 1)).step_by(3) {
-            let event = EventType::from_repr(stream.read_u8().unwrap()).unwrap();
-            if event == EventType::GameStart {
-                start_len = stream.read_u16::<BigEndian>().unwrap();
-                break;
+            let event = stream.read_u8().unwrap();
+            let size = stream.read_u16::<BigEndian>().unwrap();
+            if event == EventType::GameStart as u8 {
+                start_len = size;
+            } else if event == EventType::GameEnd as u8 {
+                end_len = Some(size);
             }
         }
 
@@ -379,6 +433,21 @@ impl Game {
 
         let (game_start, version, players) = GameStart::parse(raw_start)?;
 
+        // ------------------------------------- game end --------------------------------------- //
+        // GameEnd is the last event in the raw block, so it can be read without walking every frame.
+        // Replays that were cut off (e.g. by a crash) won't have one
+        let mut game_end = None;
+        if let Some(end_len) = end_len {
+            let end_offset = raw_length.saturating_sub(end_len as u64 + 1);
+            stream.seek(SeekFrom::Start(end_offset)).unwrap();
+
+            if stream.read_u8().unwrap() == EventType::GameEnd as u8 {
+                let mut buf = vec![0; end_len.into()];
+                stream.read_exact(&mut buf).unwrap();
+                game_end = Some(parse_gameend(Bytes::from(buf)));
+            }
+        }
+
         stream.seek(SeekFrom::Start(raw_length)).unwrap();
 
         let mut metadata_header = [0; 11];
@@ -417,7 +486,7 @@ impl Game {
             metadata: Arc::new(Metadata {
                 version,
                 start: game_start,
-                end: None,
+                end: game_end,
                 duration,
                 total_frames,
                 rolled_back_frames: None,
@@ -1,4 +1,4 @@
-use std::sync::Arc;
+use std::{ops::Range, sync::Arc};
 
 use ssbm_utils::enums::{character::Costume, Character, Port};
 
@@ -35,6 +35,51 @@ pub struct Player {
     pub frames: Frames,
     /// None if Player.character is not Ice Climbers, otherwise contains Nana's `Frames` object.
     pub nana_frames: Option<Frames>,
+    /// Every span of frames spent as a single character, in order. Only Zelda/Sheik can transform
+    /// mid-game, so this usually contains a single segment. Empty if frames weren't parsed
+    pub transformations: Vec<CharacterSegment>,
+}
+
+impl Player {
+    /// Returns the character the player was on the given frame (-123 indexed), accounting for
+    /// Zelda/Sheik transformations. Falls back to `Player::character` if the frame is out of range
+    pub fn character_at(&self, frame_index: i32) -> Character {
+        self.transformations
+            .iter()
+            .find(|x| x.frames.contains(&frame_index))
+            .map_or(self.character, |x| x.character)
+    }
+}
+
+/// A contiguous span of frames during which the player was a single character
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CharacterSegment {
+    pub character: Character,
+    /// Frame indexes (-123 indexed) covered by this segment. The end is exclusive
+    pub frames: Range<i32>,
+}
+
+/// Splits the player's frames into spans of the same character. Frames where the character can't
+/// be determined are assigned to the current segment
+pub(crate) fn find_transformations(frames: &Frames, default: Character) -> Vec<CharacterSegment> {
+    let mut result: Vec<CharacterSegment> = Vec::new();
+
+    for i in 0..frames.len() {
+        let frame_index = i as i32 - 123;
+        let character = frames.character(i);
+
+        match result.last_mut() {
+            Some(segment) if character.is_none_or(|c| c == segment.character) => {
+                segment.frames.end = frame_index + 1
+            }
+            _ => result.push(CharacterSegment {
+                character: character.unwrap_or(default),
+                frames: frame_index..frame_index + 1,
+            }),
+        }
+    }
+
+    result
 }
 
 /// Records information on which Dashback and Shielddrop toggles are activated. Possible values for
@@ -73,7 +73,7 @@ impl Game {
 impl From<&InputStats> for DataFrame {
     fn from(value: &InputStats) -> Self {
         use crate::columns::InputStats as col;
-        df!(
+        let mut df = df!(
             col::Digital.into() => value.digital.clone(),
             col::Joystick.into() => value.joystick.clone(),
             col::Cstick.into() => value.cstick.clone(),
@@ -81,7 +81,75 @@ impl From<&InputStats> for DataFrame {
             col::APM.into() => value.apm.clone(),
             col::TriggerPref.into() => value.trigger_pref.iter().map(|x| x.to_string()).collect::<Vec<_>>(),
             col::JumpPref.into() => value.jump_pref.iter().map(|x| x.to_string()).collect::<Vec<_>>(),
-    ).unwrap()
+            col::NeutralAPM.into() => value.neutral_apm.clone(),
+            col::PunishAPM.into() => value.punish_apm.clone(),
+            col::HitstunAPM.into() => value.hitstun_apm.clone(),
+    ).unwrap();
+
+        let button = |name: &str, f: fn(&ButtonCounts) -> u32| {
+            Series::new(name, value.buttons.iter().map(f).collect::<Vec<_>>())
+        };
+
+        df.with_column(
+            StructChunked::new(
+                col::Buttons.into(),
+                &[
+                    button("A", |x| x.a),
+                    button("B", |x| x.b),
+                    button("X", |x| x.x),
+                    button("Y", |x| x.y),
+                    button("Z", |x| x.z),
+                    button("L", |x| x.l),
+                    button("R", |x| x.r),
+                    button("START", |x| x.start),
+                    button("DPAD_UP", |x| x.d_up),
+                    button("DPAD_DOWN", |x| x.d_down),
+                    button("DPAD_LEFT", |x| x.d_left),
+                    button("DPAD_RIGHT", |x| x.d_right),
+                ],
+            )
+            .unwrap()
+            .into_series(),
+        )
+        .unwrap();
+
+        df.with_column(
+            StructChunked::new(
+                col::StickFlicks.into(),
+                &(0..8)
+                    .map(|i| {
+                        Series::new(
+                            StickRegion::from_repr(i as i8).unwrap().into(),
+                            value.stick_flicks.iter().map(|x| x[i]).collect::<Vec<_>>(),
+                        )
+                    })
+                    .collect::<Vec<_>>(),
+            )
+            .unwrap()
+            .into_series(),
+        )
+        .unwrap();
+
+        df
+    }
+}
+
+impl InputStats {
+    /// Per-frame input data, e.g. for plotting input timelines
+    pub fn timeline_df(&self) -> DataFrame {
+        use crate::columns::InputTimeline as col;
+        let v_s = vec![
+            Series::new(
+                col::FrameIndex.into(),
+                (0..self.new_input.len() as i32)
+                    .map(|i| i - 123)
+                    .collect::<Vec<_>>(),
+            ),
+            Series::new(col::NewInput.into(), self.new_input.to_vec()),
+            Series::new(col::Phase.into(), as_vec_static_str(self.phase.to_vec())),
+        ];
+
+        DataFrame::new(v_s).unwrap()
     }
 }
 
@@ -115,6 +183,17 @@ impl From<&WavedashStats> for DataFrame {
             )
             .unwrap()
             .into_series(),
+            Series::new(col::AirdodgeDelay.into(), val.airdodge_delay.clone()),
+            Series::new(col::AngleDeviation.into(), val.angle_deviation.clone()),
+            Series::new(col::Distance.into(), val.distance.clone()),
+            Series::new(
+                col::Ground.into(),
+                val.ground
+                    .iter()
+                    .map(|x| x.map(<&'static str>::from))
+                    .collect::<Vec<_>>(),
+            ),
+            Series::new(col::OutOfShield.into(), val.out_of_shield.clone()),
         ];
 
         DataFrame::new(vec_series).unwrap()
@@ -136,6 +215,20 @@ impl WavedashStats {
                 .count()
                 .alias("Wavelands"),
             col(clm::Angle.into()).mean().alias("AvgAngle"),
+            col(clm::AngleDeviation.into())
+                .gt_eq(lit(-PERFECT_ANGLE_TOLERANCE))
+                .and(col(clm::AngleDeviation.into()).lt_eq(lit(PERFECT_ANGLE_TOLERANCE)))
+                .cast(DataType::Float32)
+                .mean()
+                .alias("PerfectAnglePercent"),
+            col(clm::AirdodgeDelay.into())
+                .mean()
+                .alias("AvgAirdodgeDelay"),
+            col(clm::Distance.into()).mean().alias("AvgDistance"),
+            col(clm::OutOfShield.into())
+                .filter(col(clm::OutOfShield.into()).eq(lit(true)))
+                .count()
+                .alias("OutOfShield"),
         ])
         .collect()
         .ok()
@@ -166,6 +259,7 @@ impl From<&LCancelStats> for DataFrame {
             Series::new(col::LCancel.into(), value.l_cancel.clone()),
             Series::new(col::TriggerFrame.into(), value.trigger_input_frame.clone()),
             Series::new(col::Position.into(), value.position.iter().map(Into::<&'static str>::into).collect::<Vec<_>>()),
+            Series::new(col::PlatformHeight.into(), value.platform_height.clone()),
             Series::new(col::Fastfall.into(), value.fastfall.clone()),
             Series::new(col::InputDuringHitlag.into(), value.during_hitlag.clone()),
         ];
@@ -246,6 +340,8 @@ impl From<&DefenseStats> for DataFrame {
             Series::new(col::Stocks.into(), val.stocks_remaining.clone()),
             Series::new(col::Percent.into(), val.percent.clone()),
             Series::new(col::DamageTaken.into(), val.damage_taken.clone()),
+            Series::new(col::UnstaledDamage.into(), val.unstaled_damage.clone()),
+            Series::new(col::Staleness.into(), val.staleness.clone()),
             Series::new(col::LastHitBy.into(), as_vec_static_str(val.last_hit_by.clone())),
             Series::new(
                 col::StateBeforeHit.into(),
@@ -401,6 +497,7 @@ impl From<&TechStats> for DataFrame {
             .unwrap()
             .into_series(),
             Series::new(clm::Location.into(), as_vec_static_str(value.location.clone())),
+            Series::new(clm::PlatformHeight.into(), value.platform_height.clone()),
             Series::new(clm::TowardsCenter.into(), value.towards_center.clone()),
             Series::new(clm::TowardsOpnt.into(), value.towards_opponent.clone()),
             Series::new(clm::OpntDistance.into(), value.opnt_distance.clone()),
@@ -410,6 +507,348 @@ impl From<&TechStats> for DataFrame {
     }
 }
 
+impl From<&RecoveryStats> for DataFrame {
+    fn from(value: &RecoveryStats) -> Self {
+        use crate::columns::RecoveryStats as clm;
+        let v_s = vec![
+            Series::new(clm::FrameIndex.into(), value.frame_index.clone()),
+            Series::new(clm::Stocks.into(), value.stocks.clone()),
+            Series::new(clm::Percent.into(), value.percent.clone()),
+            Series::new(clm::LastHitBy.into(), as_vec_static_str(value.last_hit_by.clone())),
+            Series::new(clm::Side.into(), as_vec_static_str(value.side.clone())),
+            Series::new(clm::JumpsRemaining.into(), value.jumps_remaining.clone()),
+            StructChunked::new(
+                clm::HitstunEnd.into(),
+                &[
+                    Series::new("x", value.hitstun_end.iter().map(|p| p.x).collect::<Vec<_>>()),
+                    Series::new("y", value.hitstun_end.iter().map(|p| p.y).collect::<Vec<_>>()),
+                ],
+            )
+            .unwrap()
+            .into_series(),
+            Series::new(clm::DistFromStage.into(), value.dist_from_stage.clone()),
+            Series::new(
+                clm::Options.into(),
+                value
+                    .options
+                    .iter()
+                    .map(|x| Series::new("", as_vec_static_str(x.clone())))
+                    .collect::<Vec<_>>(),
+            ),
+            Series::new(clm::UpB.into(), value.up_b.clone()),
+            Series::new(clm::PseudoJump.into(), value.pseudo_jump.clone()),
+            Series::new(clm::Duration.into(), value.duration.clone()),
+            Series::new(clm::Outcome.into(), as_vec_static_str(value.outcome.clone())),
+            Series::new(clm::StockLost.into(), value.stock_lost.clone()),
+            Series::new(clm::RandallInvolved.into(), value.randall_involved.clone()),
+        ];
+
+        DataFrame::new(v_s).unwrap()
+    }
+}
+
+impl From<&EdgeguardStats> for DataFrame {
+    fn from(value: &EdgeguardStats) -> Self {
+        use crate::columns::EdgeguardStats as clm;
+        let v_s = vec![
+            Series::new(clm::FrameIndex.into(), value.frame_index.clone()),
+            Series::new(clm::OpntStocks.into(), value.opnt_stocks.clone()),
+            Series::new(clm::StartPercent.into(), value.start_percent.clone()),
+            Series::new(clm::EndPercent.into(), value.end_percent.clone()),
+            Series::new(
+                clm::SentOffstageBy.into(),
+                as_vec_static_str(value.sent_offstage_by.clone()),
+            ),
+            Series::new(
+                clm::Options.into(),
+                value
+                    .options
+                    .iter()
+                    .map(|x| Series::new("", as_vec_static_str(x.clone())))
+                    .collect::<Vec<_>>(),
+            ),
+            Series::new(clm::TimeToLedge.into(), value.time_to_ledge.clone()),
+            Series::new(clm::Duration.into(), value.duration.clone()),
+            Series::new(clm::Outcome.into(), as_vec_static_str(value.outcome.clone())),
+        ];
+
+        DataFrame::new(v_s).unwrap()
+    }
+}
+
+impl From<&LedgeStats> for DataFrame {
+    fn from(value: &LedgeStats) -> Self {
+        use crate::columns::LedgeStats as clm;
+        let v_s = vec![
+            Series::new(clm::FrameIndex.into(), value.frame_index.clone()),
+            Series::new(clm::Stocks.into(), value.stocks.clone()),
+            Series::new(clm::Percent.into(), value.percent.clone()),
+            StructChunked::new(
+                clm::Position.into(),
+                &[
+                    Series::new("x", value.position.iter().map(|p| p.x).collect::<Vec<_>>()),
+                    Series::new("y", value.position.iter().map(|p| p.y).collect::<Vec<_>>()),
+                ],
+            )
+            .unwrap()
+            .into_series(),
+            Series::new(clm::FramesOnLedge.into(), value.frames_on_ledge.clone()),
+            Series::new(clm::Option.into(), as_vec_static_str(value.option.clone())),
+            Series::new(clm::Galint.into(), value.galint.clone()),
+            Series::new(clm::Punished.into(), value.punished.clone()),
+        ];
+
+        DataFrame::new(v_s).unwrap()
+    }
+}
+
+impl From<&ConversionStats> for DataFrame {
+    fn from(value: &ConversionStats) -> Self {
+        use crate::columns::ConversionStats as clm;
+        let v_s = vec![
+            Series::new(clm::FrameIndex.into(), value.frame_index.clone()),
+            Series::new(clm::EndFrame.into(), value.end_frame.clone()),
+            Series::new(clm::OpntStocks.into(), value.opnt_stocks.clone()),
+            Series::new(clm::StartPercent.into(), value.start_percent.clone()),
+            Series::new(clm::EndPercent.into(), value.end_percent.clone()),
+            Series::new(
+                clm::OpeningAttack.into(),
+                as_vec_static_str(value.opening_attack.clone()),
+            ),
+            Series::new(
+                clm::OpeningType.into(),
+                as_vec_static_str(value.opening_type.clone()),
+            ),
+            Series::new(clm::HitCount.into(), value.hit_count.clone()),
+            Series::new(clm::DidKill.into(), value.did_kill.clone()),
+        ];
+
+        DataFrame::new(v_s).unwrap()
+    }
+}
+
+impl From<&KillStats> for DataFrame {
+    fn from(value: &KillStats) -> Self {
+        use crate::columns::KillStats as clm;
+        let v_s = vec![
+            Series::new(clm::FrameIndex.into(), value.frame_index.clone()),
+            Series::new(clm::Stocks.into(), value.stocks.clone()),
+            Series::new(clm::Percent.into(), value.percent.clone()),
+            Series::new(clm::KilledBy.into(), as_vec_static_str(value.killed_by.clone())),
+            Series::new(
+                clm::Blastzone.into(),
+                value
+                    .blastzone
+                    .iter()
+                    .map(|x| x.map(<&'static str>::from))
+                    .collect::<Vec<_>>(),
+            ),
+            Series::new(clm::SelfDestruct.into(), value.self_destruct.clone()),
+            Series::new(clm::OpntPercent.into(), value.opnt_percent.clone()),
+            Series::new(clm::EarlyKill.into(), value.early_kill.clone()),
+            Series::new(
+                clm::SurvivedHighPercent.into(),
+                value.survived_high_percent.clone(),
+            ),
+        ];
+
+        DataFrame::new(v_s).unwrap()
+    }
+}
+
+impl From<&ShieldStats> for DataFrame {
+    fn from(value: &ShieldStats) -> Self {
+        use crate::columns::ShieldStats as clm;
+        let v_s = vec![
+            Series::new(clm::FrameIndex.into(), value.frame_index.clone()),
+            Series::new(clm::Stocks.into(), value.stocks.clone()),
+            Series::new(clm::Attack.into(), as_vec_static_str(value.attack.clone())),
+            Series::new(clm::ShieldHealth.into(), value.shield_health.clone()),
+            Series::new(clm::ShieldDamage.into(), value.shield_damage.clone()),
+            Series::new(clm::Powershield.into(), value.powershield.clone()),
+            Series::new(clm::Poked.into(), value.poked.clone()),
+            Series::new(clm::Broken.into(), value.broken.clone()),
+            Series::new(
+                clm::OoSOption.into(),
+                value
+                    .oos_option
+                    .iter()
+                    .map(|x| x.map(<&'static str>::from))
+                    .collect::<Vec<_>>(),
+            ),
+            Series::new(clm::OoSDelay.into(), value.oos_delay.clone()),
+        ];
+
+        DataFrame::new(v_s).unwrap()
+    }
+}
+
+impl From<&PowershieldStats> for DataFrame {
+    fn from(value: &PowershieldStats) -> Self {
+        use crate::columns::PowershieldStats as clm;
+        let v_s = vec![
+            Series::new(clm::FrameIndex.into(), value.frame_index.clone()),
+            Series::new(clm::Stocks.into(), value.stocks.clone()),
+            Series::new(clm::Attack.into(), as_vec_static_str(value.attack.clone())),
+            Series::new(clm::PsType.into(), as_vec_static_str(value.ps_type.clone())),
+            Series::new(clm::ShieldFrame.into(), value.shield_frame.clone()),
+            Series::new(clm::TriggerFrame.into(), value.trigger_frame.clone()),
+            Series::new(clm::FrameAdvantage.into(), value.frame_advantage.clone()),
+        ];
+
+        DataFrame::new(v_s).unwrap()
+    }
+}
+
+impl From<&GrabStats> for DataFrame {
+    fn from(value: &GrabStats) -> Self {
+        use crate::columns::GrabStats as clm;
+        let v_s = vec![
+            Series::new(clm::FrameIndex.into(), value.frame_index.clone()),
+            Series::new(clm::Stocks.into(), value.stocks.clone()),
+            Series::new(clm::OpntPercent.into(), value.opnt_percent.clone()),
+            Series::new(clm::GrabType.into(), as_vec_static_str(value.grab_type.clone())),
+            Series::new(clm::Success.into(), value.success.clone()),
+            Series::new(clm::Pummels.into(), value.pummels.clone()),
+            Series::new(
+                clm::Throw.into(),
+                value
+                    .throw
+                    .iter()
+                    .map(|x| x.map(<&'static str>::from))
+                    .collect::<Vec<_>>(),
+            ),
+            Series::new(clm::Techchase.into(), value.techchase.clone()),
+            Series::new(clm::Regrab.into(), value.regrab.clone()),
+            Series::new(clm::Chaingrab.into(), value.chaingrab.clone()),
+            Series::new(clm::Damage.into(), value.damage.clone()),
+        ];
+
+        DataFrame::new(v_s).unwrap()
+    }
+}
+
+impl From<&MoveStats> for DataFrame {
+    fn from(value: &MoveStats) -> Self {
+        use crate::columns::MoveStats as clm;
+        let v_s = vec![
+            Series::new(clm::Attack.into(), as_vec_static_str(value.attack.clone())),
+            Series::new(clm::Started.into(), value.started.clone()),
+            Series::new(clm::Hit.into(), value.hit.clone()),
+            Series::new(clm::Shielded.into(), value.shielded.clone()),
+            Series::new(clm::Whiffed.into(), value.whiffed.clone()),
+            Series::new(clm::TotalDamage.into(), value.total_damage.clone()),
+            Series::new(clm::AvgDamage.into(), value.avg_damage.clone()),
+            Series::new(clm::AvgUnstaledDamage.into(), value.avg_unstaled_damage.clone()),
+            Series::new(clm::AvgStaleness.into(), value.avg_staleness.clone()),
+        ];
+
+        DataFrame::new(v_s).unwrap()
+    }
+}
+
+impl From<&HitStats> for DataFrame {
+    fn from(value: &HitStats) -> Self {
+        use crate::columns::HitStats as clm;
+        let v_s = vec![
+            Series::new(clm::FrameIndex.into(), value.frame_index.clone()),
+            Series::new(clm::Attacker.into(), as_vec_static_str(value.attacker.clone())),
+            Series::new(clm::Source.into(), as_vec_static_str(value.source.clone())),
+            Series::new(
+                clm::Item.into(),
+                value
+                    .item
+                    .iter()
+                    .map(|x| x.map(<&'static str>::from))
+                    .collect::<Vec<_>>(),
+            ),
+            Series::new(clm::SpawnId.into(), value.spawn_id.clone()),
+            Series::new(clm::Victim.into(), as_vec_static_str(value.victim.clone())),
+            Series::new(clm::VictimNana.into(), value.victim_nana.clone()),
+            Series::new(clm::Attack.into(), as_vec_static_str(value.attack.clone())),
+            Series::new(clm::Damage.into(), value.damage.clone()),
+            Series::new(clm::HitlagFrames.into(), value.hitlag_frames.clone()),
+            StructChunked::new(
+                clm::Knockback.into(),
+                &[
+                    Series::new("x", value.knockback.iter().map(|v| v.x).collect::<Vec<_>>()),
+                    Series::new("y", value.knockback.iter().map(|v| v.y).collect::<Vec<_>>()),
+                ],
+            )
+            .unwrap()
+            .into_series(),
+        ];
+
+        DataFrame::new(v_s).unwrap()
+    }
+}
+
+impl From<&IcsStats> for DataFrame {
+    fn from(value: &IcsStats) -> Self {
+        use crate::columns::IcsStats as clm;
+        let v_s = vec![
+            Series::new(clm::FrameIndex.into(), value.frame_index.clone()),
+            Series::new(clm::Stocks.into(), value.stocks.clone()),
+            Series::new(clm::Event.into(), as_vec_static_str(value.event.clone())),
+            Series::new(clm::Duration.into(), value.duration.clone()),
+        ];
+
+        DataFrame::new(v_s).unwrap()
+    }
+}
+
+impl From<&MovementStats> for DataFrame {
+    fn from(value: &MovementStats) -> Self {
+        use crate::columns::MovementStats as clm;
+        let v_s = vec![
+            Series::new(clm::FrameIndex.into(), value.frame_index.clone()),
+            Series::new(clm::Stocks.into(), value.stocks.clone()),
+            Series::new(clm::Movement.into(), as_vec_static_str(value.movement.clone())),
+            Series::new(clm::Success.into(), value.success.clone()),
+            Series::new(
+                clm::ControllerFix.into(),
+                value
+                    .controller_fix
+                    .iter()
+                    .map(|x| x.map(<&'static str>::from))
+                    .collect::<Vec<_>>(),
+            ),
+            Series::new(clm::Dashes.into(), value.dashes.clone()),
+            Series::new(clm::Cadence.into(), value.cadence.clone()),
+        ];
+
+        DataFrame::new(v_s).unwrap()
+    }
+}
+
+impl From<&ControllerDiagnostics> for DataFrame {
+    fn from(value: &ControllerDiagnostics) -> Self {
+        use crate::columns::ControllerDiagnostics as clm;
+        let v_s = vec![
+            Series::new(
+                clm::ControllerType.into(),
+                as_vec_static_str(value.controller_type.clone()),
+            ),
+            Series::new(
+                clm::Gate.into(),
+                value
+                    .gate
+                    .iter()
+                    .map(|x| Series::new("", x.to_vec()))
+                    .collect::<Vec<_>>(),
+            ),
+            Series::new(clm::Snapbacks.into(), value.snapbacks.clone()),
+            Series::new(clm::Jitter.into(), value.jitter.clone()),
+            Series::new(clm::LTrigger.into(), as_vec_static_str(value.l_trigger.clone())),
+            Series::new(clm::RTrigger.into(), as_vec_static_str(value.r_trigger.clone())),
+            Series::new(clm::LMax.into(), value.l_max.clone()),
+            Series::new(clm::RMax.into(), value.r_max.clone()),
+        ];
+
+        DataFrame::new(v_s).unwrap()
+    }
+}
+
 impl From<PostFrames> for DataFrame {
     fn from(val: PostFrames) -> Self {
         let len = val.len();
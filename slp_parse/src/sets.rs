@@ -0,0 +1,262 @@
+use std::{collections::BTreeMap, sync::Arc, time::Duration};
+
+use ssbm_utils::enums::{Character, Port, StageID};
+
+use crate::{
+    events::game_end::Placement,
+    game::{Game, GameMetadata, GameStub},
+};
+
+/// Maximum time between the end of one game and the start of the next for them to be considered
+/// part of the same set. Only used for replays that predate match ids (< v3.14.0)
+pub const SET_GAP: Duration = Duration::from_secs(5 * 60);
+
+/// Anything that can be grouped into a `Set`. Implemented for both `Game` and `GameStub` so sets
+/// can be built without having to fully parse every replay.
+pub trait SetEntry: GameMetadata {
+    /// Returns (port, character, connect code) for both players in port order
+    fn set_players(&self) -> [(Port, Character, Option<&str>); 2];
+
+    /// Returns the port of the player who won the game, if it can be determined
+    fn game_winner(&self) -> Option<Port>;
+}
+
+impl SetEntry for Game {
+    fn set_players(&self) -> [(Port, Character, Option<&str>); 2] {
+        self.players
+            .each_ref()
+            .map(|p| (p.port, p.character, p.connect_code.as_deref()))
+    }
+
+    fn game_winner(&self) -> Option<Port> {
+        self.winner()
+    }
+}
+
+impl SetEntry for GameStub {
+    fn set_players(&self) -> [(Port, Character, Option<&str>); 2] {
+        self.players
+            .each_ref()
+            .map(|p| (p.port, p.character, p.connect_code.as_deref()))
+    }
+
+    /// Stubs don't contain frame data, so the winner can only be pulled from the GameEnd event.
+    fn game_winner(&self) -> Option<Port> {
+        if self.ended_by_lras() {
+            return self.lras_winner(self.players.each_ref().map(|p| p.port));
+        }
+
+        self.placements()?
+            .iter()
+            .find(|(_, v)| **v == Placement::Win)
+            .map(|(k, _)| *k)
+    }
+}
+
+/// Stable identifier for a player across games. Ports can change between games in a set (e.g.
+/// different ranked lobbies), so connect codes are preferred when available.
+fn player_key(port: Port, code: Option<&str>) -> String {
+    match code {
+        Some(c) if !c.is_empty() => c.to_ascii_uppercase(),
+        _ => port.to_string(),
+    }
+}
+
+fn player_keys<G: SetEntry>(game: &G) -> [String; 2] {
+    game.set_players()
+        .map(|(port, _, code)| player_key(port, code))
+}
+
+/// The stage and characters chosen for a single game in a set. Characters and winner are aligned
+/// to `Set::players`
+#[derive(Debug, Clone, PartialEq)]
+pub struct GamePick {
+    pub game_number: u32,
+    pub stage: StageID,
+    pub characters: [Character; 2],
+    /// index into `Set::players`
+    pub winner: Option<usize>,
+}
+
+/// A stage pick made by the loser of the previous game.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Counterpick {
+    pub game_number: u32,
+    /// index into `Set::players`. None if the previous game had no conclusive winner
+    pub picker: Option<usize>,
+    pub stage: StageID,
+    /// The character the picker played in the counterpicked game
+    pub character: Option<Character>,
+    /// True if the picker won the counterpicked game
+    pub won: Option<bool>,
+}
+
+/// A group of games played between the same 2 players, in the order they were played.
+#[derive(Debug, Clone)]
+pub struct Set<G: SetEntry> {
+    /// The shared match id. `None` if the set was assembled heuristically from pre-3.14.0 replays
+    pub match_id: Option<Arc<String>>,
+    /// Connect codes (or port names if no connect code is available) of both players, taken from
+    /// the first game of the set
+    pub players: [String; 2],
+    pub games: Vec<G>,
+}
+
+impl<G: SetEntry> Set<G> {
+    fn new(match_id: Option<Arc<String>>, games: Vec<G>) -> Self {
+        let players = player_keys(&games[0]);
+        Self {
+            match_id,
+            players,
+            games,
+        }
+    }
+
+    /// Returns the index into `self.players` of the given game's player
+    fn player_idx(&self, port: Port, code: Option<&str>) -> Option<usize> {
+        let key = player_key(port, code);
+        self.players.iter().position(|x| *x == key)
+    }
+
+    /// Returns the index into `self.players` of the winner of the given game
+    fn winner_idx(&self, game: &G) -> Option<usize> {
+        let winner = game.game_winner()?;
+        let (port, _, code) = game.set_players().into_iter().find(|x| x.0 == winner)?;
+        self.player_idx(port, code)
+    }
+
+    /// Number of games won by each player, aligned to `self.players`
+    pub fn score(&self) -> [u32; 2] {
+        let mut result = [0, 0];
+        for game in &self.games {
+            if let Some(i) = self.winner_idx(game) {
+                result[i] += 1;
+            }
+        }
+        result
+    }
+
+    /// Returns the connect code (or port) of the player with more game wins. None if the score is
+    /// tied
+    pub fn winner(&self) -> Option<&str> {
+        let [a, b] = self.score();
+        match a.cmp(&b) {
+            std::cmp::Ordering::Less => Some(&self.players[1]),
+            std::cmp::Ordering::Greater => Some(&self.players[0]),
+            std::cmp::Ordering::Equal => None,
+        }
+    }
+
+    pub fn picks(&self) -> Vec<GamePick> {
+        self.games
+            .iter()
+            .enumerate()
+            .map(|(i, game)| {
+                let mut characters = [Character::default(); 2];
+                for (port, character, code) in game.set_players() {
+                    if let Some(idx) = self.player_idx(port, code) {
+                        characters[idx] = character;
+                    }
+                }
+
+                GamePick {
+                    game_number: game.game_number().unwrap_or(i as u32 + 1),
+                    stage: game.stage(),
+                    characters,
+                    winner: self.winner_idx(game),
+                }
+            })
+            .collect()
+    }
+
+    /// Stage picks for every game after the first, attributed to the loser of the previous game
+    pub fn counterpicks(&self) -> Vec<Counterpick> {
+        let picks = self.picks();
+
+        picks
+            .windows(2)
+            .map(|w| {
+                let (prev, curr) = (&w[0], &w[1]);
+                let picker = prev.winner.map(|x| 1 - x);
+
+                Counterpick {
+                    game_number: curr.game_number,
+                    picker,
+                    stage: curr.stage,
+                    character: picker.map(|x| curr.characters[x]),
+                    won: picker.and_then(|x| curr.winner.map(|w| w == x)),
+                }
+            })
+            .collect()
+    }
+}
+
+/// Groups the given games into sets. Games with a match id (>= v3.14.0) are grouped by match id
+/// and ordered by game number and tiebreak number. Older games are grouped heuristically: games
+/// between the same 2 players that start within `SET_GAP` of the previous game ending are
+/// considered part of the same set.
+///
+/// Sets are returned oldest -> newest by the date of their first game
+pub fn group_sets<G: SetEntry>(games: Vec<G>) -> Vec<Set<G>> {
+    let mut by_id: BTreeMap<Arc<String>, Vec<G>> = BTreeMap::new();
+    let mut loose = Vec::new();
+
+    for game in games {
+        let id = game.match_id();
+        if id.is_empty() {
+            loose.push(game);
+        } else {
+            by_id.entry(id).or_default().push(game);
+        }
+    }
+
+    let mut result = Vec::new();
+
+    for (id, mut games) in by_id {
+        games.sort_by_key(|g| {
+            (
+                g.game_number().unwrap_or(0),
+                g.tiebreak_number().unwrap_or(0),
+                g.date().unix_timestamp_nanos(),
+            )
+        });
+        result.push(Set::new(Some(id), games));
+    }
+
+    // ------------------------------------- heuristic fallback ------------------------------------- //
+
+    loose.sort_by_key(|g| g.date().unix_timestamp_nanos());
+
+    let mut curr: Vec<G> = Vec::new();
+    let mut curr_players: [String; 2] = Default::default();
+
+    for game in loose {
+        let mut players = player_keys(&game);
+        players.sort();
+
+        let contiguous = curr.last().is_some_and(|prev| {
+            let prev_end = prev.date() + prev.duration();
+            // dates default to the unix epoch, so a missing date can never be grouped
+            prev.date().unix_timestamp() != 0
+                && game.date() >= prev_end
+                && game.date() - prev_end <= SET_GAP
+        });
+
+        let same_set = contiguous && players == curr_players;
+
+        if !same_set && !curr.is_empty() {
+            result.push(Set::new(None, std::mem::take(&mut curr)));
+        }
+
+        curr_players = players;
+        curr.push(game);
+    }
+
+    if !curr.is_empty() {
+        result.push(Set::new(None, curr));
+    }
+
+    result.sort_by_key(|s| s.games[0].date().unix_timestamp_nanos());
+
+    result
+}
@@ -1,23 +1,49 @@
+pub(crate) mod combo_scorer;
 pub(crate) mod combos;
+pub(crate) mod controller;
+pub(crate) mod conversions;
 pub(crate) mod defense;
+pub(crate) mod edgeguard;
+pub(crate) mod grabs;
+pub(crate) mod hits;
+pub(crate) mod ics;
 pub(crate) mod inputs;
 pub(crate) mod items;
+pub(crate) mod kills;
 pub(crate) mod lcancel;
-// pub(crate) mod recovery;
+pub(crate) mod ledge;
+pub(crate) mod movement;
+pub(crate) mod moves;
+pub(crate) mod powershield;
+pub(crate) mod recovery;
+pub(crate) mod shield;
 pub(crate) mod tech;
 pub(crate) mod wavedash;
 
 use std::sync::Arc;
 
-pub use combos::{Combos, Combo, Move, rate_falco_combos};
+pub use combo_scorer::{ComboScorer, MoveRule, ScoringProfile};
+pub use combos::{Combos, Combo, ComboBreak, ComboConfig, Move};
 
+pub use controller::{ControllerDiagnostics, ControllerType, TriggerType};
+pub use conversions::{ConversionStats, OpeningType, CONVERSION_RESET_WINDOW};
 pub use defense::DefenseStats;
-pub use inputs::InputStats;
+pub use edgeguard::{EdgeguardOption, EdgeguardOutcome, EdgeguardStats};
+pub use grabs::{GrabStats, GrabType, ThrowDirection};
+pub use hits::{HitSource, HitStats};
+pub use ics::{IcsEvent, IcsStats};
+pub use inputs::{ButtonCounts, InputPhase, InputStats};
 pub use items::ItemStats;
+pub use kills::KillStats;
 pub use lcancel::LCancelStats;
-// pub use recovery::RecoveryStats;
+pub use ledge::{LedgeOption, LedgeStats};
+pub use movement::{MovementStats, MovementType};
+pub use moves::MoveStats;
+pub use powershield::{PowershieldStats, PowershieldType};
+pub use recovery::{RecoveryOption, RecoveryOutcome, RecoveryStats};
+pub use shield::{OoSOption, ShieldStats};
 pub use tech::TechStats;
-pub use wavedash::WavedashStats;
+pub use wavedash::{WavedashStats, PERFECT_ANGLE_TOLERANCE, PERFECT_WAVEDASH_ANGLE};
 
 use strum_macros::{EnumString, IntoStaticStr};
 
@@ -37,6 +63,19 @@ pub enum StatType {
     Tech,
     Item,
     Defense,
+    Recovery,
+    Edgeguard,
+    Ledge,
+    Conversion,
+    Kill,
+    Shield,
+    Powershield,
+    Grab,
+    Move,
+    Hit,
+    Ics,
+    Movement,
+    Controller,
 }
 
 // TODO unify the way stats are instantiated. Some translate from rows, some have the columns built
@@ -57,6 +96,48 @@ pub struct Stats {
     pub item: Option<ItemStats>,
     /// Minimum Replay Version 3.5.0
     pub defense: Option<DefenseStats>,
+    /// Minimum Replay Version: 2.0.0
+    pub recovery: Option<RecoveryStats>,
+    /// Minimum Replay Version: 2.0.0
+    pub edgeguard: Option<EdgeguardStats>,
+    /// Minimum Replay Version: 2.0.0
+    ///
+    /// GALINT requires replay version >= 2.1.0
+    pub ledge: Option<LedgeStats>,
+    /// Minimum Replay Version: Any
+    pub conversions: ConversionStats,
+    /// Minimum Replay Version: 2.0.0
+    pub kills: Option<KillStats>,
+    /// Minimum Replay Version: 2.0.0
+    pub shield: Option<ShieldStats>,
+    /// Minimum Replay Version: 2.0.0
+    pub powershield: Option<PowershieldStats>,
+    /// Minimum Replay Version: 2.0.0
+    pub grab: Option<GrabStats>,
+    /// Minimum Replay Version: 2.0.0
+    pub moves: Option<MoveStats>,
+    /// Minimum Replay Version: 3.16.0
+    pub hits: Option<HitStats>,
+    /// Minimum Replay Version: 3.5.0
+    ///
+    /// None if the player isn't Ice Climbers. Hits taken by Nana
+    pub nana_defense: Option<DefenseStats>,
+    /// Minimum Replay Version: 2.0.0
+    ///
+    /// None if the player isn't Ice Climbers
+    pub nana_tech: Option<TechStats>,
+    /// Minimum Replay Version: 2.0.0
+    ///
+    /// None if the player isn't Ice Climbers. Includes Nana dying alongside Popo
+    pub nana_kills: Option<KillStats>,
+    /// Minimum Replay Version: Any
+    ///
+    /// None if the player isn't Ice Climbers
+    pub ics: Option<IcsStats>,
+    /// Minimum Replay Version: 2.0.0
+    pub movement: Option<MovementStats>,
+    /// Minimum Replay Version: 3.15.0
+    pub controller: Option<ControllerDiagnostics>,
 }
 
 // Eventually maybe i'll use this and add convenience methods for bulk parsing
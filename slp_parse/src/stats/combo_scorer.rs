@@ -0,0 +1,525 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fs::File,
+    io::BufReader,
+    path::Path,
+    str::FromStr,
+};
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use ssbm_utils::{
+    enums::{stage::Stage, ActionState, Attack, BitFlags, Character, Flags},
+    mf,
+};
+
+use crate::{
+    player::Player,
+    stats::{Combo, Move},
+    Game, GameMetadata,
+};
+
+/// Awards `points` for every move in the combo whose attack is in `attacks`. Negative values act as
+/// penalties
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MoveRule {
+    pub attacks: Vec<Attack>,
+    pub points: i32,
+}
+
+impl MoveRule {
+    pub fn new(attacks: &[Attack], points: i32) -> Self {
+        Self {
+            attacks: attacks.to_vec(),
+            points,
+        }
+    }
+
+    pub fn matches(&self, mv: &Move) -> bool {
+        self.attacks.contains(&mv.move_id)
+    }
+}
+
+/// The weights used to rate a single character's combos. Every field is optional when loading from
+/// a file, missing fields use the values from `ScoringProfile::default()`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ScoringProfile {
+    /// Points per move in the combo
+    pub points_per_move: i32,
+    /// Cap on the points awarded by `points_per_move`. Very long combos tend to be meandering
+    pub max_move_points: i32,
+    /// Extra points per move, by attack. Added on top of `points_per_move`, and not capped
+    pub move_weights: HashMap<Attack, i32>,
+    pub move_rules: Vec<MoveRule>,
+    /// Combos with fewer moves are penalized by `short_combo_penalty`
+    pub min_moves: usize,
+    pub short_combo_penalty: i32,
+    /// Combos lasting at least this many frames are penalized by `long_combo_penalty`
+    pub max_duration: i32,
+    pub long_combo_penalty: i32,
+    pub kill_bonus: i32,
+    /// Bonus for killing on the opponent's last stock
+    pub game_ender_bonus: i32,
+    /// Bonus for killing from 0%
+    pub zero_to_death_bonus: i32,
+    /// Bonus for a kill whose last hit connected with both players offstage
+    pub offstage_finish_bonus: i32,
+    /// Penalty for combos whose first hit connected offstage. Targets long edgeguard strings
+    pub offstage_start_penalty: i32,
+    /// Points per unique attack in the combo
+    pub variety_bonus: i32,
+    /// Points per percent dealt
+    pub damage_bonus: f32,
+    /// Scaled by the fraction of the combo the opponent spent in hitstun
+    pub hitstun_bonus: i32,
+    /// Penalty for each time the player was hit during the combo
+    pub got_hit_penalty: i32,
+    /// Penalty for each grounded attack that didn't connect during the combo
+    pub missed_attack_penalty: i32,
+    /// Bonus for each hit on a downed opponent
+    pub jab_reset_bonus: i32,
+    /// Bonus for each hit that connected behind the player (or in front of them for back airs)
+    pub reverse_hit_bonus: i32,
+    /// Attacks that can't be meaningfully reversed, and are excluded from `reverse_hit_bonus`
+    pub non_reversible: Vec<Attack>,
+    /// Bonus for each missed tech or tech option punished during the combo
+    pub tech_punish_bonus: i32,
+}
+
+impl Default for ScoringProfile {
+    fn default() -> Self {
+        Self {
+            points_per_move: 10,
+            max_move_points: 70,
+            move_weights: HashMap::new(),
+            move_rules: vec![MoveRule::new(
+                &[
+                    Attack::F_TILT,
+                    Attack::D_TILT,
+                    Attack::FAIR,
+                    Attack::UAIR,
+                    Attack::FORWARD_THROW,
+                    Attack::BACK_THROW,
+                    Attack::UP_THROW,
+                    Attack::DOWN_THROW,
+                ],
+                2,
+            )],
+            min_moves: 3,
+            short_combo_penalty: 100,
+            max_duration: 900,
+            long_combo_penalty: 100,
+            kill_bonus: 100,
+            game_ender_bonus: 10,
+            zero_to_death_bonus: 10,
+            offstage_finish_bonus: 5,
+            offstage_start_penalty: 10,
+            variety_bonus: 2,
+            damage_bonus: 0.0,
+            hitstun_bonus: 50,
+            got_hit_penalty: 5,
+            missed_attack_penalty: 5,
+            jab_reset_bonus: 4,
+            reverse_hit_bonus: 2,
+            non_reversible: vec![Attack::NEUTRAL_SPECIAL, Attack::DOWN_SPECIAL],
+            tech_punish_bonus: 5,
+        }
+    }
+}
+
+impl ScoringProfile {
+    /// Returns the built-in profile for the given character. These are the generic defaults with
+    /// adjustments for characters whose specials are commonly used as filler (e.g. lasers) or are
+    /// especially flashy (e.g. rest)
+    pub fn for_character(character: Character) -> Self {
+        use Character as C;
+        let mut profile = Self::default();
+
+        match character {
+            C::Fox | C::Falco => {
+                // lasers are boring, side b into other moves is not
+                profile
+                    .move_rules
+                    .push(MoveRule::new(&[Attack::NEUTRAL_SPECIAL], -2));
+                profile
+                    .move_rules
+                    .push(MoveRule::new(&[Attack::SIDE_SPECIAL], 2));
+            }
+            C::Samus
+            | C::Link
+            | C::YoungLink
+            | C::Sheik
+            | C::Pikachu
+            | C::Pichu
+            | C::Mario
+            | C::DrMario
+            | C::Luigi
+            | C::Ness => {
+                profile
+                    .move_rules
+                    .push(MoveRule::new(&[Attack::NEUTRAL_SPECIAL], -2));
+            }
+            C::Peach => {
+                profile
+                    .move_rules
+                    .push(MoveRule::new(&[Attack::DOWN_SPECIAL], -2));
+            }
+            C::Jigglypuff => {
+                profile.move_weights.insert(Attack::DOWN_SPECIAL, 10);
+            }
+            C::CaptainFalcon | C::Ganondorf => {
+                profile.move_weights.insert(Attack::NEUTRAL_SPECIAL, 10);
+            }
+            C::Marth | C::Roy => {
+                // grab combos and chaingrabs are the bread and butter, not the highlights
+                profile.move_rules[0].attacks.retain(|x| !x.is_throw());
+            }
+            C::Popo | C::Nana | C::IceClimbers => {
+                profile.max_duration = 1800;
+                profile.move_rules[0].attacks.retain(|x| !x.is_throw());
+            }
+            _ => (),
+        }
+
+        profile
+    }
+}
+
+/// Rates combos based on a set of per-character `ScoringProfile`s. Characters without a custom
+/// profile use `ScoringProfile::for_character`.
+///
+/// Profiles can be loaded from a json file mapping character names to (partial) profiles. Character
+/// names are case-insensitive and accept the same nicknames as `Character::try_from`:
+///
+/// ```json
+/// {
+///     "Falco": { "kill_bonus": 50, "move_weights": { "DAIR": 3 } },
+///     "Marth": { "min_moves": 4 }
+/// }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct ComboScorer {
+    pub profiles: HashMap<Character, ScoringProfile>,
+}
+
+impl ComboScorer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a scorer using the profiles in the given json file
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self> {
+        let mut scorer = Self::new();
+        scorer.load_profiles(path)?;
+        Ok(scorer)
+    }
+
+    /// Loads the profiles in the given json file, replacing any existing profiles for the same
+    /// characters
+    pub fn load_profiles(&mut self, path: impl AsRef<Path>) -> Result<()> {
+        let reader = BufReader::new(File::open(path)?);
+        let profiles: HashMap<String, ScoringProfile> = serde_json::from_reader(reader)?;
+
+        for (name, profile) in profiles {
+            let character = Character::from_str(&name)
+                .map_err(|_| anyhow!("Unknown character '{name}' in combo scoring profiles"))?;
+            self.profiles.insert(character, profile);
+        }
+
+        Ok(())
+    }
+
+    pub fn set_profile(&mut self, character: Character, profile: ScoringProfile) {
+        self.profiles.insert(character, profile);
+    }
+
+    pub fn profile(&self, character: Character) -> ScoringProfile {
+        self.profiles
+            .get(&character)
+            .cloned()
+            .unwrap_or_else(|| ScoringProfile::for_character(character))
+    }
+
+    /// Rates the combos of the player with the given connect code across all of the games. Games
+    /// that don't contain the connect code are skipped
+    pub fn rate_combos(&self, connect_code: &str, games: &[Game]) -> Vec<(Combo, i32)> {
+        let mut result = Vec::new();
+
+        for game in games {
+            let Ok(player) = game.player_by_code(connect_code) else {
+                continue;
+            };
+            let Ok(opnt) = game.opponent_by_code(connect_code) else {
+                continue;
+            };
+
+            let stage = Stage::from_id(game.stage());
+            for combo in player.combos.iter() {
+                let profile = self.profile(player.character_at(combo.start_frame));
+                let rating = score_combo(&profile, combo, &player, &opnt, &stage);
+                result.push((combo.clone(), rating));
+            }
+        }
+
+        result
+    }
+
+    /// Rates a single combo performed by `player` on `opnt`, using the profile of the character the
+    /// player was when the combo started
+    pub fn score(&self, combo: &Combo, player: &Player, opnt: &Player, stage: &Stage) -> i32 {
+        let profile = self.profile(player.character_at(combo.start_frame));
+        score_combo(&profile, combo, player, opnt, stage)
+    }
+}
+
+fn score_combo(
+    profile: &ScoringProfile,
+    combo: &Combo,
+    player: &Player,
+    opnt: &Player,
+    stage: &Stage,
+) -> i32 {
+    let Some(first_move) = combo.move_list.first() else {
+        return 0;
+    };
+    let last_move = combo.move_list.last().unwrap();
+    let mut rating: i32 = 0;
+
+    // ---------------------------------------- combo-wide ---------------------------------------- //
+    if combo.move_list.len() < profile.min_moves {
+        rating -= profile.short_combo_penalty;
+    }
+
+    if combo.duration() >= profile.max_duration {
+        rating -= profile.long_combo_penalty;
+    }
+
+    if combo.did_kill {
+        rating += profile.kill_bonus;
+
+        if combo.start_percent == 0.0 {
+            rating += profile.zero_to_death_bonus;
+        }
+
+        if combo.is_game_ender() {
+            rating += profile.game_ender_bonus;
+        }
+
+        if stage.is_offstage(last_move.player_position)
+            && stage.is_offstage(last_move.opponent_position)
+        {
+            rating += profile.offstage_finish_bonus;
+        }
+    }
+
+    if stage.is_offstage(first_move.opponent_position) {
+        rating -= profile.offstage_start_penalty;
+    }
+
+    rating += (combo.move_list.len() as i32 * profile.points_per_move).min(profile.max_move_points);
+    rating += (combo.damage().max(0.0) * profile.damage_bonus) as i32;
+
+    // favors combos where the opponent spends more of the time in hitstun, which balances out
+    // the move points by letting shorter combos earn extra points
+    if let Some(flags) = opnt.frames.post.flags.as_ref() {
+        let hitstun_frames = flags[mf!(combo.start_frame)..mf!(combo.end_frame)]
+            .iter()
+            .filter(|&&x| Flags::HITSTUN.contained_by(x))
+            .count();
+
+        if combo.duration() > 0 {
+            rating += ((hitstun_frames as f32 / combo.duration() as f32)
+                * profile.hitstun_bonus as f32) as i32;
+        }
+    }
+
+    if let Some(defense) = player.stats.defense.as_ref() {
+        let times_hit = defense
+            .frame_index
+            .iter()
+            .filter(|x| combo.melee_frame_range().contains(x))
+            .count();
+        rating -= times_hit as i32 * profile.got_hit_penalty;
+    }
+
+    if let Some(techs) = opnt.stats.tech.as_ref() {
+        let punished = techs
+            .frame_index
+            .iter()
+            .zip(techs.punished.iter())
+            .filter(|(x, &punished)| punished && combo.melee_frame_range().contains(x))
+            .count();
+        rating += punished as i32 * profile.tech_punish_bonus;
+    }
+
+    // grounded attacks that never hit. Specials are ignored since they're often used for utility
+    if let Some(flags) = player.frames.post.flags.as_deref() {
+        let states = &player.frames.post.action_state;
+        let mut attack = false;
+        let mut hit = false;
+
+        for i in combo.frame_range() {
+            let state = states[i];
+
+            if ActionState::GROUND_ATTACK_RANGE.contains(&state) {
+                if state != states[i - 1] {
+                    attack = true;
+                    hit = false;
+                }
+                if Flags::HITLAG.contained_by(flags[i])
+                    && !Flags::DEFENDER_HITLAG.contained_by(flags[i])
+                {
+                    hit = true;
+                }
+            } else {
+                if attack && !hit {
+                    rating -= profile.missed_attack_penalty;
+                }
+                attack = false;
+                hit = false;
+            }
+        }
+    }
+
+    // ----------------------------------------- per-move ----------------------------------------- //
+    let mut moveset = HashSet::new();
+
+    for mv in &combo.move_list {
+        moveset.insert(mv.move_id);
+
+        rating += profile.move_weights.get(&mv.move_id).copied().unwrap_or(0);
+        rating += profile
+            .move_rules
+            .iter()
+            .filter(|rule| rule.matches(mv))
+            .map(|rule| rule.points)
+            .sum::<i32>();
+
+        if matches!(
+            ActionState::from_repr(opnt.frames.post.action_state[mf!(mv.frame_index)])
+                .unwrap_or_default(),
+            ActionState::DOWN_DAMAGE_U | ActionState::DOWN_DAMAGE_D
+        ) {
+            rating += profile.jab_reset_bonus;
+        }
+
+        // orientation left is negative, right is positive. Normalizing the opponent's position to
+        // the player's makes it negative if they're farther left and positive if they're farther
+        // right, so the product is negative when the opponent is behind the player. Back air is
+        // the exception, since a reverse hit means facing the opponent.
+        let relative_pos =
+            mv.player_orientation as i8 as f32 * (mv.opponent_position.x - mv.player_position.x);
+        let reversed = if mv.move_id == Attack::BAIR {
+            relative_pos.is_sign_positive()
+        } else {
+            relative_pos.is_sign_negative()
+        };
+
+        if reversed && !profile.non_reversible.contains(&mv.move_id) {
+            rating += profile.reverse_hit_bonus;
+        }
+    }
+
+    rating += profile.variety_bonus * moveset.len() as i32;
+
+    rating
+}
+
+#[cfg(test)]
+mod test {
+    use std::{path::PathBuf, sync::Arc};
+
+    use ssbm_utils::{
+        enums::{stage::StageID, Orientation},
+        types::Position,
+    };
+
+    use super::*;
+    use crate::events::post_frame::PostFrames;
+
+    fn write_profiles(name: &str, json: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(name);
+        std::fs::write(&path, json).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_score_from_file() {
+        let path = write_profiles(
+            "slp_parse_test_score_profiles.json",
+            r#"{
+                "falco": {
+                    "points_per_move": 5,
+                    "move_weights": { "DAIR": 3 },
+                    "move_rules": [],
+                    "kill_bonus": 50,
+                    "variety_bonus": 1
+                }
+            }"#,
+        );
+        let scorer = ComboScorer::from_file(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let profile = scorer.profile(Character::Falco);
+        assert_eq!(profile.points_per_move, 5);
+        // fields missing from the file keep their default values
+        assert_eq!(profile.min_moves, ScoringProfile::default().min_moves);
+
+        let player_pos = Position::new(0.0, 0.0);
+        let opnt_pos = Position::new(5.0, 0.0);
+        let mut combo = Combo::new(Arc::new(PathBuf::new()), opnt_pos, 4, 2, 30.0, 0);
+        combo.move_list = [
+            (0, Attack::DAIR, 10.0),
+            (10, Attack::DAIR, 10.0),
+            (20, Attack::U_SMASH, 70.0),
+        ]
+        .into_iter()
+        .map(|(frame, attack, damage)| {
+            Move::new(
+                frame,
+                attack,
+                damage,
+                opnt_pos,
+                player_pos,
+                Orientation::RIGHT,
+            )
+        })
+        .collect();
+        combo.did_kill = true;
+        combo.end_percent = 120.0;
+        combo.end_frame = 60;
+
+        let player = Player::default();
+        let mut opnt = Player::default();
+        opnt.frames.post = Arc::new(PostFrames {
+            action_state: vec![ActionState::WAIT as u16; 200].into_boxed_slice(),
+            ..Default::default()
+        });
+
+        let stage = Stage::from_id(StageID::YOSHIS_STORY);
+
+        // kill (50) + 3 moves (15) + 2 dairs (6) + 2 unique moves (2)
+        assert_eq!(score_combo(&profile, &combo, &player, &opnt, &stage), 73);
+    }
+
+    #[test]
+    fn test_profile_fallback() {
+        let path = write_profiles(
+            "slp_parse_test_fallback_profiles.json",
+            r#"{ "Marth": { "min_moves": 4 } }"#,
+        );
+        let scorer = ComboScorer::from_file(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(scorer.profile(Character::Marth).min_moves, 4);
+        assert_eq!(
+            scorer.profile(Character::Fox),
+            ScoringProfile::for_character(Character::Fox)
+        );
+        assert_eq!(
+            ComboScorer::new().profile(Character::Marth),
+            ScoringProfile::for_character(Character::Marth)
+        );
+    }
+}
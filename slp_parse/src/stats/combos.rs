@@ -1,5 +1,6 @@
+#![allow(non_camel_case_types)]
+
 use std::{
-    collections::HashSet,
     ops::{Deref, Range, RangeInclusive},
     path::PathBuf,
     sync::Arc,
@@ -9,21 +10,97 @@ use derive_new::new;
 use serde_json::json;
 use ssbm_utils::{
     checks::{is_grabbed, just_lost_stock},
-    enums::{stage::Stage, Attack, Character, StageID},
-    mf,
+    enums::{stage::Stage, Attack, Character, Item, StageID},
     prelude::*,
     types::Position,
 };
+use strum_macros::{Display, EnumString, IntoStaticStr};
 
-use crate::{frames::Frames, Game, GameMetadata};
+use crate::{
+    events::item_frames::ItemFrames,
+    frames::Frames,
+    stats::hits::{find_item_instance, hit_by, item_instances, resolve_item},
+};
 
 pub const COMBO_LENIENCY: u32 = 45;
 pub const PRE_COMBO_BUFFER_FRAMES: i32 = 75;
 pub const POST_COMBO_BUFFER_FRAMES: i32 = 120;
 
+/// Determines which of the opponent's states keep a combo alive. Once the opponent is no longer in
+/// one of those states, the combo ends after `ComboConfig::leniency` frames.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Display, EnumString, IntoStaticStr)]
+pub enum ComboBreak {
+    /// The combo continues until the opponent is actionable (not in hitstun, teching, downed,
+    /// offstage, dodging, shielding, etc.). Matches the official parser
+    #[default]
+    ACTIONABLE,
+    /// The combo continues only while the opponent is in hitstun, hitlag, grabbed, or thrown
+    HITSTUN_END,
+}
+
+/// Parameters for combo detection. `ComboConfig::default()` matches the official parser.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ComboConfig {
+    /// Number of frames the opponent can be outside of a combo state before the combo ends
+    pub leniency: u32,
+    /// Count grabs, pummels and throws as part of combos
+    pub grabs: bool,
+    /// Count hits from items (e.g. thrown items, bob-ombs, beam swords)
+    pub items: bool,
+    /// Count hits from projectiles (e.g. lasers, turnips, missiles)
+    pub projectiles: bool,
+    /// Combos with fewer moves are discarded
+    pub min_hits: usize,
+    pub break_on: ComboBreak,
+}
+
+impl Default for ComboConfig {
+    fn default() -> Self {
+        Self {
+            leniency: COMBO_LENIENCY,
+            grabs: true,
+            items: true,
+            projectiles: true,
+            min_hits: 1,
+            break_on: ComboBreak::ACTIONABLE,
+        }
+    }
+}
+
+impl ComboConfig {
+    /// Returns true if the hit landed on frame `i` should count towards a combo. If the item that
+    /// caused the hit isn't known, projectile hits are detected by the player not entering hitlag
+    /// on the hit, which requires replay version 2.0.0 or later
+    fn counts_hit(
+        &self,
+        plyr_frames: &Frames,
+        attack: Attack,
+        item: Option<Item>,
+        i: usize,
+    ) -> bool {
+        if attack.is_throw() || attack == Attack::PUMMEL {
+            self.grabs
+        } else if let Some(item) = item {
+            if item.is_character_item() {
+                self.projectiles
+            } else {
+                self.items
+            }
+        } else if attack.is_item() {
+            self.items
+        } else if plyr_frames.post.flags.is_some() && !plyr_frames.in_hitlag(i) {
+            self.projectiles
+        } else {
+            true
+        }
+    }
+}
+
 #[derive(Debug, Clone, new)]
 pub struct Move {
     pub frame_index: i32,
+    /// The player's last landed attack. For item and projectile hits this may be stale (e.g. a laser
+    /// fired after a throw), see `item`
     pub move_id: Attack,
     #[new(value = "1")]
     pub hit_count: u32,
@@ -31,6 +108,12 @@ pub struct Move {
     pub opponent_position: Position,
     pub player_position: Position,
     pub player_orientation: Orientation,
+    /// The item or projectile that hit the opponent, if any. Requires replay version 3.16.0
+    #[new(default)]
+    pub item: Option<Item>,
+    /// The hit was landed by Nana rather than the player. Requires replay version 3.16.0
+    #[new(default)]
+    pub nana: bool,
 }
 
 #[derive(Debug, Clone, new)]
@@ -134,28 +217,47 @@ impl Deref for Combos {
 pub struct ComboState {
     reset_counter: u32,
     last_hit_animation: Option<u16>,
+    /// Spawn id of the item that landed the last recorded hit. Hits from a different item instance
+    /// always start a new move
+    last_hit_spawn: Option<u32>,
+    /// The opponent was put into hitstun by a hit that doesn't count, so their hitstun shouldn't
+    /// start a new combo
+    ignore_punish: bool,
 }
 
-impl Default for ComboState {
-    fn default() -> Self {
+impl ComboState {
+    fn new(leniency: u32) -> Self {
         Self {
-            reset_counter: COMBO_LENIENCY,
+            reset_counter: leniency,
             last_hit_animation: None,
+            last_hit_spawn: None,
+            ignore_punish: false,
         }
     }
 }
 
+impl Default for ComboState {
+    fn default() -> Self {
+        Self::new(COMBO_LENIENCY)
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
 pub fn find_combos(
     plyr_frames: &Frames,
+    plyr_nana: Option<&Frames>,
     opnt_frames: &Frames,
+    items: Option<&ItemFrames>,
     stage_id: StageID,
     _player_char: Character,
     path: Arc<PathBuf>,
+    config: &ComboConfig,
 ) -> Combos {
     let mut result = Vec::new();
+    let item_instances = item_instances(items);
 
     let mut event = None;
-    let mut combo_state = ComboState::default();
+    let mut combo_state = ComboState::new(config.leniency);
     let stage = Stage::from_id(stage_id);
 
     for i in 1..plyr_frames.len() {
@@ -168,7 +270,8 @@ pub fn find_combos(
         let opnt_position = opnt_frames.post.position[i];
         let opnt_is_damaged = opnt_frames.damaged_state(i);
         let opnt_is_in_hitstun = opnt_frames.in_hitstun(i);
-        let opnt_is_grabbed = opnt_frames.grabbed(i) || opnt_frames.cmd_grabbed(i);
+        let opnt_is_grabbed =
+            config.grabs && (opnt_frames.grabbed(i) || opnt_frames.cmd_grabbed(i));
         let opnt_damage_taken = opnt_frames.damage_taken(i);
         let opnt_prev_percent = opnt_frames.post.percent[i - 1];
         // let opnt_damage_taken = get_damage_taken(opnt_percent, opnt_prev_percent);
@@ -194,7 +297,32 @@ pub fn find_combos(
             combo_state.last_hit_animation = None
         }
 
-        if opnt_is_in_hitstun || opnt_is_grabbed || opnt_is_damaged {
+        // nana's hits use her own attack and hitlag rather than popo's
+        let nana = plyr_nana.filter(|&x| opnt_damage_taken > 0.0 && hit_by(opnt_frames, x, i));
+        let attacker = nana.unwrap_or(plyr_frames);
+
+        let attack = Attack::from_repr(attacker.post.last_attack_landed[i]).unwrap_or(Attack::NONE);
+
+        let item_row = opnt_frames
+            .post
+            .instance_hit_by
+            .as_ref()
+            .filter(|_| opnt_damage_taken > 0.0)
+            .and_then(|hit_by| find_item_instance(&item_instances, hit_by[i], i));
+        let item = item_row.map(|row| resolve_item(items.unwrap(), row));
+        let item_spawn = item_row.map(|row| items.unwrap().spawn_id[row]);
+
+        let hit_counts = opnt_damage_taken <= 0.0 || config.counts_hit(attacker, attack, item, i);
+
+        let opnt_is_punished = opnt_is_in_hitstun || opnt_is_grabbed || opnt_is_damaged;
+
+        if event.is_none() && !hit_counts {
+            combo_state.ignore_punish = true;
+        } else if !opnt_is_punished || (event.is_none() && opnt_damage_taken > 0.0) {
+            combo_state.ignore_punish = false;
+        }
+
+        if opnt_is_punished && !combo_state.ignore_punish {
             // if the opponent has been hit and there's no active combo, start a combo
             if event.is_none() {
                 event = Some(Combo::new(
@@ -212,16 +340,21 @@ pub fn find_combos(
 
             // TODO BUG slippi-js has issues with this too, but magnifying glass damage while in
             // a knockback animation will count as a move
-            if opnt_damage_taken > 0.0 {
-                if combo_state.last_hit_animation.is_none() {
-                    event.as_mut().unwrap().move_list.push(Move::new(
+            if opnt_damage_taken > 0.0 && hit_counts {
+                if combo_state.last_hit_animation.is_none()
+                    || item_spawn != combo_state.last_hit_spawn
+                {
+                    let mut mv = Move::new(
                         i as i32 - 123,
-                        Attack::from_repr(plyr_frames.post.last_attack_landed[i]).unwrap(),
+                        attack,
                         opnt_damage_taken,
                         opnt_position,
                         plyr_position,
                         Orientation::from_repr(plyr_frames.post.orientation[i] as i8).unwrap(),
-                    ));
+                    );
+                    mv.item = item;
+                    mv.nana = nana.is_some();
+                    event.as_mut().unwrap().move_list.push(mv);
                 } else {
                     let temp = event
                         .as_mut()
@@ -233,6 +366,7 @@ pub fn find_combos(
                     temp.hit_count += 1;
                     temp.damage += opnt_damage_taken;
                 }
+                combo_state.last_hit_spawn = item_spawn;
             }
         }
 
@@ -254,24 +388,30 @@ pub fn find_combos(
         let opnt_is_special_fall = opnt_frames.special_fall(i);
         let opnt_is_upb_lag = opnt_frames.upb_lag(i);
 
-        if opnt_is_damaged
-            || opnt_is_grabbed
-            || opnt_is_in_hitlag
-            || opnt_is_in_hitstun
-            || opnt_is_offstage
-            || opnt_is_dodging
-            || opnt_is_shielding
-            || opnt_shield_broken
-            || opnt_is_ledge_action
-            || opnt_is_special_fall
-            || opnt_is_upb_lag
-            || opnt_is_teching
-            || opnt_is_downed
-            || opnt_is_dying
-        {
-            combo_state.reset_counter = COMBO_LENIENCY;
+        let in_combo_state =
+            opnt_is_damaged || opnt_is_grabbed || opnt_is_in_hitlag || opnt_is_in_hitstun;
+
+        let extend = match config.break_on {
+            ComboBreak::HITSTUN_END => in_combo_state || (config.grabs && opnt_frames.thrown(i)),
+            ComboBreak::ACTIONABLE => {
+                in_combo_state
+                    || opnt_is_offstage
+                    || opnt_is_dodging
+                    || opnt_is_shielding
+                    || opnt_shield_broken
+                    || opnt_is_ledge_action
+                    || opnt_is_special_fall
+                    || opnt_is_upb_lag
+                    || opnt_is_teching
+                    || opnt_is_downed
+                    || opnt_is_dying
+            }
+        };
+
+        if extend {
+            combo_state.reset_counter = config.leniency;
         } else {
-            combo_state.reset_counter -= 1;
+            combo_state.reset_counter = combo_state.reset_counter.saturating_sub(1);
         }
 
         let plyr_is_grabbed = is_grabbed(plyr_state);
@@ -279,7 +419,7 @@ pub fn find_combos(
             just_lost_stock(plyr_frames.post.stocks[i], plyr_frames.post.stocks[i - 1]);
 
         let mut should_terminate =
-            combo_state.reset_counter == 0 || plyr_is_grabbed || plyr_lost_stock;
+            (!extend && combo_state.reset_counter == 0) || plyr_is_grabbed || plyr_lost_stock;
 
         if just_lost_stock(opnt_frames.post.stocks[i], opnt_frames.post.stocks[i - 1]) {
             should_terminate = true;
@@ -292,7 +432,7 @@ pub fn find_combos(
             temp.end_percent = opnt_prev_percent;
             temp.end_position = opnt_frames.post.position[i - 1];
 
-            if !temp.move_list.is_empty() {
+            if !temp.move_list.is_empty() && temp.move_list.len() >= config.min_hits {
                 result.push(event.unwrap());
             }
 
@@ -302,189 +442,3 @@ pub fn find_combos(
 
     Combos { data: result, path }
 }
-
-pub fn rate_falco_combos(connect_code: &str, games: &[Game]) -> Vec<(Combo, i32)> {
-    let mut result = Vec::new();
-
-    for game in games {
-        let player = game.player_by_code(connect_code);
-
-        if player.is_err() {
-            continue;
-        }
-
-        let player = player.unwrap();
-        if player.character != Character::Falco {
-            continue;
-        }
-
-        let stage = Stage::from_id(game.stage());
-        let opnt = game.opponent_by_code(connect_code).unwrap();
-
-        for combo in &player.combos.data {
-            let mut rating: i32 = 0;
-
-            if !combo.did_kill || combo.move_list.len() < 3 {
-                rating -= 100;
-            } else if combo.start_percent == 0.0 {
-                // 0-death bonus
-                rating += 10;
-            }
-
-            // really really long combos are rarely worth watching
-            if combo.end_frame - combo.start_frame >= 900 {
-                rating -= 100;
-            }
-
-            if combo.is_game_ender() {
-                rating += 10;
-            }
-
-            // incentivize longer combos, but cap at 60. Longer combos tend to be meandering and
-            // not very interesting.
-            rating += (combo.move_list.len() * 10).min(70) as i32;
-
-            // incentivize combos where the opponent is in hitstun more of the time - balances out
-            // the above by allowing shorter combos to get extra points
-            let hitstun_frames = opnt.frames.post.flags.as_ref().unwrap()
-                [mf!(combo.start_frame)..mf!(combo.end_frame)]
-                .iter()
-                .fold(0, |acc, x| {
-                    if (Flags::HITSTUN).contained_by(*x) {
-                        acc + 1
-                    } else {
-                        acc
-                    }
-                });
-
-            rating += ((hitstun_frames as f32 / combo.duration() as f32) * 50.0) as i32;
-
-            // deduct points if the player gets hit
-            for def in &player.stats.defense.as_ref().unwrap().frame_index {
-                if (combo.start_frame..combo.end_frame).contains(def) {
-                    rating -= 5;
-                }
-            }
-
-            // deduct points if the first hit is offstage - this targets certain long edgeguard
-            // strings that aren't particularly interesting
-            if stage.is_offstage(combo.move_list[0].opponent_position) {
-                rating -= 10;
-            }
-
-            {
-                // deduct points for missing moves. Does not count B moves, as those can be used for
-                // utility purposes
-                let mut attack = false;
-                let mut hit = false;
-                let flags = player.frames.post.flags.as_deref().unwrap();
-
-                for i in combo.frame_range() {
-                    let prev = player.frames.post.action_state[i - 1];
-                    let state = player.frames.post.action_state[i];
-
-                    if ActionState::GROUND_ATTACK_RANGE.contains(&state) {
-                        if state != prev {
-                            attack = true;
-                            hit = false;
-                        }
-                        if Flags::HITLAG.contained_by(flags[i])
-                            && !Flags::DEFENDER_HITLAG.contained_by(flags[i])
-                        {
-                            hit = true;
-                        }
-                    } else {
-                        if attack && !hit {
-                            rating -= 5;
-                        }
-                        attack = false;
-                        hit = false;
-                    }
-                }
-            }
-
-            let mut moveset = HashSet::new();
-
-            for mv in &combo.move_list {
-                moveset.insert(mv.move_id);
-
-                // non-standard moves are cool
-                if is_unusual(mv) {
-                    rating += 2;
-                }
-                // lasers are boring
-                if mv.move_id == Attack::NEUTRAL_SPECIAL {
-                    rating -= 2;
-                }
-
-                // jab resets
-                if matches!(
-                    ActionState::from_repr(opnt.frames.post.action_state[mf!(mv.frame_index)])
-                        .unwrap_or_default(),
-                    ActionState::DOWN_DAMAGE_U | ActionState::DOWN_DAMAGE_D
-                ) {
-                    rating += 4;
-                }
-
-                // reverse hits
-                // neat trick: orientation left is negative, right is positive. Normalizing the
-                // opponent's position to the player will make it negative if they are farther left
-                // and positive if they are farther right. If the player is facing the same direction
-                // as their opponent is positioned relative to them, the result will be positive
-                // (right/pos * right/pos or left/neg * left/neg).
-                let relative_pos = mv.player_orientation as i8 as f32
-                    * (mv.opponent_position.x - mv.player_position.x);
-                // make sure it's a move that can actually be meaningfully reversed
-                // also bair is a special case in that a reverse hit means you're facing your opnt
-                if !matches!(mv.move_id, Attack::NEUTRAL_SPECIAL | Attack::DOWN_SPECIAL)
-                    && relative_pos.is_sign_negative()
-                    && mv.move_id != Attack::BAIR
-                    || relative_pos.is_sign_positive() && mv.move_id == Attack::BAIR
-                {
-                    rating += 2;
-                }
-            }
-
-            // tech reads are cool
-            if let Some(techs) = opnt.stats.tech.as_ref() {
-                for i in 0..techs.frame_index.len() {
-                    if !(combo.start_frame..combo.end_frame).contains(&techs.frame_index[i]) {
-                        continue;
-                    }
-
-                    if techs.punished[i] {
-                        rating += 5;
-                    }
-                }
-            }
-
-            // offstage edgeguards are cool
-            if stage.is_offstage(combo.move_list.last().unwrap().player_position)
-                && stage.is_offstage(combo.move_list.last().unwrap().opponent_position)
-            {
-                rating += 5;
-            }
-
-            rating += 2 * moveset.len() as i32;
-
-            result.push((combo.clone(), rating));
-        }
-    }
-
-    result
-}
-
-fn is_unusual(mv: &Move) -> bool {
-    matches!(
-        mv.move_id,
-        Attack::F_TILT
-            | Attack::D_TILT
-            | Attack::FAIR
-            | Attack::UAIR
-            | Attack::SIDE_SPECIAL
-            | Attack::FORWARD_THROW
-            | Attack::BACK_THROW
-            | Attack::UP_THROW
-            | Attack::DOWN_THROW
-    )
-}
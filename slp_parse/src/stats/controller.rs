@@ -0,0 +1,235 @@
+#![allow(non_camel_case_types)]
+
+use std::collections::HashMap;
+
+use ssbm_utils::enums::{BitFlags, ControllerInput};
+use strum_macros::{Display, EnumString, IntoStaticStr};
+
+use crate::{frames::Frames, stats::Stat};
+
+/// Raw stick values are in hardware units, where the game treats a distance of 80 from center as a
+/// fully tilted stick
+pub const RAW_STICK_MAX: f32 = 80.0;
+/// Raw distance from center below which the stick is considered at rest. Matches the game's
+/// 0.2875 deadzone
+pub const RAW_DEADZONE: f32 = 0.2875 * RAW_STICK_MAX;
+/// Min raw distance along an axis the stick must be held at before being released for the rebound
+/// to count as a snapback
+pub const SNAPBACK_RELEASE: f32 = 0.8 * RAW_STICK_MAX;
+/// Max number of frames between the release and the rebound, and between the rebound and the stick
+/// settling back into the deadzone
+pub const SNAPBACK_WINDOW: usize = 4;
+/// Max raw change along an axis between adjacent frames for it to count as jitter
+pub const JITTER_MAX: i8 = 2;
+/// Ratio of held stick positions that must be reused at least `DIGITAL_MIN_REPEATS` times for the
+/// controller to be considered digital. Analog sticks rarely land on the exact same raw coordinate
+pub const DIGITAL_REPEAT_RATIO: f32 = 0.95;
+pub const DIGITAL_MIN_REPEATS: u32 = 3;
+/// Min number of frames the stick must be held outside of the deadzone before guessing the
+/// controller type
+pub const MIN_HELD_FRAMES: usize = 300;
+/// Min analog value for the game to register a trigger press (i.e. lightshield). Triggers that are
+/// digitally pressed but average below this are considered plugged
+pub const ANALOG_PRESS_THRESHOLD: f32 = 0.3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Display, EnumString, IntoStaticStr)]
+pub enum ControllerType {
+    /// Gamecube controller, or any other controller with an analog stick
+    GCC,
+    /// Box-style or other all-button controller, whose stick values snap to a fixed set of
+    /// coordinates
+    DIGITAL,
+    /// The stick wasn't used enough to tell
+    UNKNOWN,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Display, EnumString, IntoStaticStr)]
+pub enum TriggerType {
+    /// The trigger reports a full range of analog values
+    ANALOG,
+    /// The trigger barely reports any analog value while digitally pressed, i.e. it has a trigger
+    /// plug or is otherwise physically blocked
+    PLUGGED,
+    /// The trigger only ever reports fully released or fully pressed
+    DIGITAL,
+    /// The trigger was never pressed
+    UNUSED,
+}
+
+/// Returns the octant of the given raw stick position, starting at up and moving clockwise. This
+/// matches the ordering of `StickRegion`, but octants are split evenly by angle rather than by the
+/// game's deadzone thresholds
+#[inline]
+fn octant(x: f32, y: f32) -> usize {
+    let angle = 90.0 - y.atan2(x).to_degrees();
+    ((angle + 22.5).rem_euclid(360.0) / 45.0) as usize % 8
+}
+
+/// Counts the number of times the stick was released from `SNAPBACK_RELEASE` or further and
+/// rebounded past center and out of the deadzone, before quickly settling back into the deadzone.
+/// Rebounds that reach `SNAPBACK_RELEASE` are assumed to be intentional (e.g. dash dancing)
+fn find_snapbacks(axis: &[i8]) -> u32 {
+    let mut count = 0;
+    let mut i = 1;
+
+    while i < axis.len() {
+        let prev = axis[i - 1] as f32;
+        let curr = axis[i] as f32;
+
+        // the stick is being released from a full tilt
+        if prev.abs() < SNAPBACK_RELEASE || curr.abs() >= prev.abs() {
+            i += 1;
+            continue;
+        }
+
+        let sign = prev.signum();
+        let rebound = (i..(i + SNAPBACK_WINDOW).min(axis.len()))
+            .find(|&k| axis[k] as f32 * sign <= -RAW_DEADZONE);
+
+        // an intentional input in the opposite direction is held rather than settling at center
+        let settled = rebound
+            .and_then(|k| {
+                ((k + 1)..(k + 1 + SNAPBACK_WINDOW).min(axis.len()))
+                    .find(|&j| (axis[j] as f32).abs() < RAW_DEADZONE)
+            })
+            .filter(|&j| (i..j).all(|k| (axis[k] as f32).abs() < SNAPBACK_RELEASE));
+
+        match settled {
+            Some(j) => {
+                count += 1;
+                i = j + 1;
+            }
+            None => i += 1,
+        }
+    }
+
+    count
+}
+
+/// Returns true if the axis briefly moved by up to `JITTER_MAX` on frame `i` and returned to its
+/// previous value on the next frame
+#[inline]
+fn is_jitter(axis: &[i8], i: usize) -> bool {
+    axis[i] != axis[i - 1]
+        && axis[i + 1] == axis[i - 1]
+        && (axis[i] as i16 - axis[i - 1] as i16).abs() <= JITTER_MAX as i16
+}
+
+fn trigger_type(analog: &[f32], buttons: &[u16], trigger: ControllerInput) -> TriggerType {
+    let pressed = buttons
+        .iter()
+        .zip(analog.iter())
+        .filter(|(&x, _)| trigger.intersects(x))
+        .map(|(_, &val)| val)
+        .collect::<Vec<_>>();
+
+    if pressed.is_empty() && analog.iter().all(|&x| x < ANALOG_PRESS_THRESHOLD) {
+        return TriggerType::UNUSED;
+    }
+
+    if !pressed.is_empty()
+        && pressed.iter().sum::<f32>() / (pressed.len() as f32) < ANALOG_PRESS_THRESHOLD
+    {
+        return TriggerType::PLUGGED;
+    }
+
+    if analog.iter().all(|&x| x == 0.0 || x == 1.0) {
+        TriggerType::DIGITAL
+    } else {
+        TriggerType::ANALOG
+    }
+}
+
+/// Guesses the controller type from how often held stick positions land on the exact same raw
+/// coordinate
+fn controller_type(raw_x: &[i8], raw_y: &[i8]) -> ControllerType {
+    let mut positions: HashMap<(i8, i8), u32> = HashMap::new();
+
+    for (&x, &y) in raw_x.iter().zip(raw_y.iter()) {
+        if (x as f32).hypot(y as f32) >= RAW_DEADZONE {
+            *positions.entry((x, y)).or_default() += 1;
+        }
+    }
+
+    let held = positions.values().sum::<u32>();
+    if (held as usize) < MIN_HELD_FRAMES {
+        return ControllerType::UNKNOWN;
+    }
+
+    let repeated = positions
+        .values()
+        .filter(|&&x| x >= DIGITAL_MIN_REPEATS)
+        .sum::<u32>();
+
+    if repeated as f32 / held as f32 >= DIGITAL_REPEAT_RATIO {
+        ControllerType::DIGITAL
+    } else {
+        ControllerType::GCC
+    }
+}
+
+/// Builds a hardware report from the player's raw controller data, to help tell whether a
+/// controller needs repair.
+///
+/// Requires replay version 3.15.0 for the raw stick y values
+pub(crate) fn find_controller(frames: &Frames) -> ControllerDiagnostics {
+    let pre = &frames.pre;
+    let (Some(raw_x), Some(raw_y)) = (pre.raw_stick_x.as_ref(), pre.raw_stick_y.as_ref()) else {
+        return ControllerDiagnostics::default();
+    };
+
+    let mut gate = [0.0; 8];
+    for (&x, &y) in raw_x.iter().zip(raw_y.iter()) {
+        let (x, y) = (x as f32, y as f32);
+        let distance = x.hypot(y);
+        if distance >= RAW_DEADZONE {
+            let octant = octant(x, y);
+            gate[octant] = f32::max(gate[octant], distance);
+        }
+    }
+
+    // frames where both axes flicker are only counted once
+    let len = raw_x.len().min(raw_y.len());
+    let jitter = (1..len.saturating_sub(1))
+        .filter(|&i| is_jitter(raw_x, i) || is_jitter(raw_y, i))
+        .count();
+
+    ControllerDiagnostics {
+        controller_type: vec![controller_type(raw_x, raw_y)],
+        gate: vec![gate],
+        snapbacks: vec![find_snapbacks(raw_x) + find_snapbacks(raw_y)],
+        jitter: vec![jitter as f32 / len.max(1) as f32],
+        l_trigger: vec![trigger_type(
+            &pre.controller_l,
+            &pre.controller_buttons,
+            ControllerInput::L,
+        )],
+        r_trigger: vec![trigger_type(
+            &pre.controller_r,
+            &pre.controller_buttons,
+            ControllerInput::R,
+        )],
+        l_max: vec![pre.controller_l.iter().copied().fold(0.0, f32::max)],
+        r_max: vec![pre.controller_r.iter().copied().fold(0.0, f32::max)],
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ControllerDiagnostics {
+    pub controller_type: Vec<ControllerType>,
+    /// Furthest raw distance from center reached in each octant, starting at up and moving
+    /// clockwise. Octants that were never reached are 0
+    pub gate: Vec<[f32; 8]>,
+    /// Number of times the stick rebounded past center after being released from a full tilt
+    pub snapbacks: Vec<u32>,
+    /// Ratio of frames on which either stick axis flickered by a few raw units and immediately back
+    pub jitter: Vec<f32>,
+    pub l_trigger: Vec<TriggerType>,
+    pub r_trigger: Vec<TriggerType>,
+    /// Max analog value reached by the L trigger
+    pub l_max: Vec<f32>,
+    /// Max analog value reached by the R trigger
+    pub r_max: Vec<f32>,
+}
+
+impl Stat for ControllerDiagnostics {}
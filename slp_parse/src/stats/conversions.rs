@@ -0,0 +1,234 @@
+#![allow(non_camel_case_types)]
+
+use ssbm_utils::enums::Attack;
+use strum_macros::{Display, EnumString, IntoStaticStr};
+
+use crate::{frames::Frames, stats::Stat};
+
+/// Number of frames the opponent can be free of hitstun/grabs before a conversion ends. Matches the
+/// official stats
+pub const CONVERSION_RESET_WINDOW: u32 = 45;
+
+/// How the conversion started, relative to the opponent's conversions
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Display, EnumString, IntoStaticStr, Default)]
+pub enum OpeningType {
+    /// Neither player was mid-conversion
+    #[default]
+    NEUTRAL_WIN,
+    /// Started while the opponent was mid-conversion on the player
+    COUNTER_HIT,
+    /// Both players started a conversion on the same frame
+    TRADE,
+}
+
+/// Every conversion the player landed on the opponent. Opening types are filled in by
+/// `find_conversions`, since they depend on the opponent's conversions
+fn conversion_spans(
+    plyr_frames: &Frames,
+    opnt_frames: &Frames,
+    reset_window: u32,
+) -> Vec<ConversionRow> {
+    let opnt = &opnt_frames.post;
+    let attacks = &plyr_frames.post.last_attack_landed;
+
+    let mut event: Option<ConversionRow> = None;
+    let mut reset_counter = 0;
+    let mut result = Vec::new();
+
+    for i in 1..plyr_frames.len() {
+        let opnt_punished =
+            opnt_frames.damaged_state(i) || opnt_frames.grabbed(i) || opnt_frames.cmd_grabbed(i);
+
+        // ----------------------------------- event detection ---------------------------------- //
+        if opnt_punished && event.is_none() {
+            event = Some(ConversionRow::new(
+                i as i32 - 123,
+                opnt.stocks[i],
+                opnt.percent[i - 1],
+                Attack::from_repr(attacks[i]).unwrap_or(Attack::NONE),
+            ));
+        }
+
+        let Some(row) = event.as_mut() else {
+            continue;
+        };
+
+        // ----------------------------------- mid-event data ----------------------------------- //
+        if opnt_frames.just_took_damage(i) {
+            row.hit_count += 1;
+        }
+
+        if opnt_punished {
+            reset_counter = 0;
+        } else {
+            reset_counter += 1;
+        }
+
+        // ------------------------------------ finalize event ----------------------------------- //
+        let did_kill = opnt_frames.just_lost_stock(i);
+
+        if did_kill || reset_counter > reset_window || i == plyr_frames.len() - 1 {
+            row.did_kill = did_kill;
+            row.end_frame = i as i32 - 123;
+            row.end_percent = opnt.percent[i - 1];
+
+            result.push(event.take().unwrap());
+            reset_counter = 0;
+        }
+    }
+
+    result
+}
+
+/// Finds every conversion (punish) the player landed on the opponent. Conversions last until the
+/// opponent has gone `reset_window` frames without being in a damaged or grabbed state, or until
+/// they lose a stock.
+pub(crate) fn find_conversions(
+    plyr_frames: &Frames,
+    opnt_frames: &Frames,
+    reset_window: u32,
+) -> ConversionStats {
+    let mut conversions = conversion_spans(plyr_frames, opnt_frames, reset_window);
+    let opnt_conversions = conversion_spans(opnt_frames, plyr_frames, reset_window);
+
+    for row in conversions.iter_mut() {
+        let trade = opnt_conversions
+            .iter()
+            .any(|x| x.frame_index == row.frame_index);
+        let counter_hit = opnt_conversions
+            .iter()
+            .any(|x| x.frame_index < row.frame_index && x.end_frame > row.frame_index);
+
+        row.opening_type = if trade {
+            OpeningType::TRADE
+        } else if counter_hit {
+            OpeningType::COUNTER_HIT
+        } else {
+            OpeningType::NEUTRAL_WIN
+        };
+    }
+
+    let mut table = ConversionStats::default();
+    for row in &conversions {
+        table.push_row(row);
+    }
+
+    table
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct ConversionStats {
+    pub frame_index: Vec<i32>,
+    pub end_frame: Vec<i32>,
+    pub opnt_stocks: Vec<u8>,
+    pub start_percent: Vec<f32>,
+    pub end_percent: Vec<f32>,
+    pub opening_attack: Vec<Attack>,
+    pub opening_type: Vec<OpeningType>,
+    pub hit_count: Vec<u32>,
+    pub did_kill: Vec<bool>,
+}
+
+impl ConversionStats {
+    pub fn push_row(&mut self, row: &ConversionRow) {
+        self.frame_index.push(row.frame_index);
+        self.end_frame.push(row.end_frame);
+        self.opnt_stocks.push(row.opnt_stocks);
+        self.start_percent.push(row.start_percent);
+        self.end_percent.push(row.end_percent);
+        self.opening_attack.push(row.opening_attack);
+        self.opening_type.push(row.opening_type);
+        self.hit_count.push(row.hit_count);
+        self.did_kill.push(row.did_kill);
+    }
+
+    /// Total number of openings (conversions), regardless of opening type
+    pub fn openings(&self) -> usize {
+        self.frame_index.len()
+    }
+
+    pub fn count_opening_type(&self, opening_type: OpeningType) -> usize {
+        self.opening_type
+            .iter()
+            .filter(|&&x| x == opening_type)
+            .count()
+    }
+
+    pub fn neutral_wins(&self) -> usize {
+        self.count_opening_type(OpeningType::NEUTRAL_WIN)
+    }
+
+    pub fn counter_hits(&self) -> usize {
+        self.count_opening_type(OpeningType::COUNTER_HIT)
+    }
+
+    pub fn trades(&self) -> usize {
+        self.count_opening_type(OpeningType::TRADE)
+    }
+
+    pub fn kills(&self) -> usize {
+        self.did_kill.iter().filter(|&&x| x).count()
+    }
+
+    pub fn total_damage(&self) -> f32 {
+        self.end_percent
+            .iter()
+            .zip(&self.start_percent)
+            .map(|(end, start)| end - start)
+            .sum()
+    }
+
+    /// Returns None if there were no openings
+    pub fn damage_per_opening(&self) -> Option<f32> {
+        (self.openings() > 0).then(|| self.total_damage() / self.openings() as f32)
+    }
+
+    /// Returns None if there were no kills
+    pub fn openings_per_kill(&self) -> Option<f32> {
+        (self.kills() > 0).then(|| self.openings() as f32 / self.kills() as f32)
+    }
+
+    /// Ratio of the player's neutral wins to the total neutral wins of both players. Returns None if
+    /// neither player won neutral
+    pub fn neutral_win_ratio(&self, opponent: &ConversionStats) -> Option<f32> {
+        let total = self.neutral_wins() + opponent.neutral_wins();
+        (total > 0).then(|| self.neutral_wins() as f32 / total as f32)
+    }
+}
+
+impl Stat for ConversionStats {}
+
+#[derive(Debug, Clone)]
+pub struct ConversionRow {
+    pub frame_index: i32,
+    pub end_frame: i32,
+    pub opnt_stocks: u8,
+    pub start_percent: f32,
+    pub end_percent: f32,
+    /// The move that started the conversion
+    pub opening_attack: Attack,
+    pub opening_type: OpeningType,
+    pub hit_count: u32,
+    pub did_kill: bool,
+}
+
+impl ConversionRow {
+    pub fn new(
+        frame_index: i32,
+        opnt_stocks: u8,
+        start_percent: f32,
+        opening_attack: Attack,
+    ) -> Self {
+        Self {
+            frame_index,
+            end_frame: frame_index,
+            opnt_stocks,
+            start_percent,
+            end_percent: start_percent,
+            opening_attack,
+            opening_type: OpeningType::default(),
+            hit_count: 0,
+            did_kill: false,
+        }
+    }
+}
@@ -3,29 +3,31 @@
 use ssbm_utils::{
     calc::{
         apply_di, get_di_efficacy, initial_x_velocity, initial_y_velocity, kb_from_initial,
-        should_kill,
-    },
-    checks::{
-        get_damage_taken, is_electric_attack, is_in_hitlag, is_thrown, is_vcancel_state,
-        just_pressed_any, just_took_damage,
+        should_kill, unstaled_damage,
     },
+    checks::{is_electric_attack, is_in_hitlag, is_thrown, is_vcancel_state, just_pressed_any},
     constants::ASDI_DIST,
     enums::{Attack, Character, EngineInput, State, StickRegion},
     types::{Degrees, Position, StickPos, Velocity},
 };
 
-use crate::{frames::Frames, stats::Stat};
+use crate::{
+    frames::Frames,
+    stats::{hits::last_attacker, Stat},
+};
 
+/// Character-dependent values (attributes, electric attacks) use the character active on each frame.
+/// `player_char` and `opnt_char` are only used when that can't be determined
 pub(crate) fn find_defense(
     plyr_frames: &Frames,
     opnt_frames: &Frames,
+    opnt_nana: Option<&Frames>,
     stage_id: u16,
     player_char: Character,
     opnt_char: Character,
 ) -> DefenseStats {
     let pre = &plyr_frames.pre;
     let post = &plyr_frames.post;
-    let attacks = &opnt_frames.post.last_attack_landed;
 
     let flags: &[u64] = post.flags.as_ref().unwrap();
     let states: &[u16] = post.action_state.as_ref();
@@ -59,8 +61,8 @@ pub(crate) fn find_defense(
         // let shielding = is_shielding_flag(flags[i]);
         // let grabbed_check = false;
 
-        let took_damage = just_took_damage(post.percent[i], post.percent[i - 1]);
-        let damage_taken = get_damage_taken(post.percent[i], post.percent[i - 1]);
+        let took_damage = plyr_frames.just_took_damage(i);
+        let damage_taken = plyr_frames.damage_taken(i);
 
         // ----------------------------------- event detection ---------------------------------- //
         // TODO check for being hit while already in hitlag
@@ -70,20 +72,27 @@ pub(crate) fn find_defense(
         // && !is_magnifying_damage(damage_taken, flags, i)
         {
             let prev_state = states[i - 1];
+            let attacker = last_attacker(plyr_frames, opnt_frames, opnt_nana, i);
 
             event = Some(DefenseRow::new(
                 i as i32 - 123,
                 post.stocks[i],
                 post.percent[i],
                 damage_taken,
-                Attack::from_repr(attacks[i]).unwrap(),
-                State::from_state_and_char(prev_state, Some(player_char)),
+                Attack::from_repr(attacker.post.last_attack_landed[i]).unwrap(),
+                State::from_state_and_char(
+                    prev_state,
+                    Some(plyr_frames.character(i - 1).unwrap_or(player_char)),
+                ),
                 grounded[i - 1],
                 post.position[i],
             ));
 
             let row = event.as_mut().unwrap();
             row.kb = post.knockback.as_ref().unwrap()[i];
+            row.staleness = attacker.staleness(row.last_hit_by, i);
+            row.unstaled_damage =
+                unstaled_damage(damage_taken, &attacker.stale_slots(row.last_hit_by, i));
 
             if row.grounded || !is_vcancel_state(prev_state) {
                 row.v_cancel = None;
@@ -121,9 +130,13 @@ pub(crate) fn find_defense(
             // if you just entered crouch, or if you crouched during a subframe event, so we're just
             // gonna check against the expected hitlag frames.
 
+            // the character that landed the hit, which may have transformed since
+            let hit_frame = (row.frame_index + 123) as usize;
+            let attacker_char = opnt_frames.character(hit_frame).unwrap_or(opnt_char);
+
             let expected_hitlag = ssbm_utils::calc::on_hit::hitlag(
                 row.damage_taken,
-                is_electric_attack(row.last_hit_by, &opnt_char),
+                is_electric_attack(row.last_hit_by, &attacker_char),
                 true,
             );
             if row.grounded {
@@ -176,7 +189,8 @@ pub(crate) fn find_defense(
                     initial_y_velocity(kb_scalar, with_di, row.grounded),
                 );
 
-                let char_stats = player_char.get_stats();
+                let character = plyr_frames.character(i).unwrap_or(player_char);
+                let char_stats = character.get_stats();
 
                 row.kills_no_di = should_kill(
                     stage_id,
@@ -244,6 +258,8 @@ pub struct DefenseStats {
     pub stocks_remaining: Vec<u8>,
     pub percent: Vec<f32>,
     pub damage_taken: Vec<f32>,
+    pub unstaled_damage: Vec<f32>,
+    pub staleness: Vec<f32>,
     pub last_hit_by: Vec<Attack>,
     pub state_before_hit: Vec<State>,
     pub grounded: Vec<bool>,
@@ -275,6 +291,8 @@ impl DefenseStats {
         self.stocks_remaining.push(stat.stocks_remaining);
         self.percent.push(stat.percent);
         self.damage_taken.push(stat.damage_taken);
+        self.unstaled_damage.push(stat.unstaled_damage);
+        self.staleness.push(stat.staleness);
         self.last_hit_by.push(stat.last_hit_by);
         self.state_before_hit.push(stat.state_before_hit);
         self.grounded.push(stat.grounded);
@@ -308,6 +326,10 @@ pub struct DefenseRow {
     pub stocks_remaining: u8,
     pub percent: f32,
     pub damage_taken: f32,
+    /// Damage of the hit before stale move negation, based on the opponent's stale move queue
+    pub unstaled_damage: f32,
+    /// Fraction of the hit's damage removed by stale move negation
+    pub staleness: f32,
     pub last_hit_by: Attack,
     pub state_before_hit: State,
     pub grounded: bool,
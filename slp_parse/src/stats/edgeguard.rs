@@ -0,0 +1,222 @@
+#![allow(non_camel_case_types)]
+
+use std::collections::HashSet;
+
+use ssbm_utils::{
+    enums::{stage::Stage, ActionState, Attack, Port},
+    types::Position,
+};
+use strum_macros::{Display, EnumString, IntoStaticStr};
+
+use crate::{events::item_frames::ItemFrames, frames::Frames, stats::Stat};
+
+/// The opponent is considered close enough to grab the ledge while they're within this distance of
+/// it
+pub const LEDGE_GRAB_RANGE: f32 = 20.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Display, EnumString, IntoStaticStr)]
+pub enum EdgeguardOption {
+    /// Held the ledge while the opponent was within grabbing range of it
+    LEDGEHOG,
+    /// Started an aerial while offstage
+    OFFSTAGE_AERIAL,
+    /// Had a projectile offstage. Requires replay version >= 3.6.0
+    PROJECTILE,
+    /// Opponent was knocked into a wall or ceiling
+    STAGE_SPIKE,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Display, EnumString, IntoStaticStr, Default)]
+pub enum EdgeguardOutcome {
+    #[default]
+    RETURNED,
+    KILLED,
+}
+
+#[allow(clippy::needless_range_loop)]
+pub(crate) fn find_edgeguards(
+    plyr_frames: &Frames,
+    opnt_frames: &Frames,
+    stage: &Stage,
+    port: Port,
+    items: Option<&ItemFrames>,
+) -> EdgeguardStats {
+    let post = &plyr_frames.post;
+    let opnt = &opnt_frames.post;
+    let opnt_grounded: &[bool] = opnt.is_grounded.as_ref().unwrap();
+
+    // melee frames on which the player had a projectile offstage
+    let projectile_frames: HashSet<i32> = match items {
+        Some(items) if items.owner.is_some() => {
+            let owner = items.owner.as_ref().unwrap();
+            (0..items.len())
+                .filter(|&i| owner[i] == port as i8 && stage.is_offstage(items.position[i]))
+                .map(|i| items.frame_index[i])
+                .collect()
+        }
+        _ => HashSet::new(),
+    };
+
+    let mut event: Option<EdgeguardRow> = None;
+    let mut table = EdgeguardStats::default();
+
+    for i in 1..plyr_frames.len() {
+        let opnt_pos = opnt.position[i];
+        let opnt_offstage = stage.is_offstage(opnt_pos);
+
+        // ----------------------------------- event detection ---------------------------------- //
+        if event.is_none() {
+            if opnt_frames.in_hitstun(i)
+                && opnt_offstage
+                && !(opnt_frames.in_hitstun(i - 1) && stage.is_offstage(opnt.position[i - 1]))
+                && !opnt_frames.dying(i)
+            {
+                event = Some(EdgeguardRow::new(
+                    i as i32 - 123,
+                    opnt.stocks[i],
+                    opnt.percent[i],
+                    Attack::from_repr(post.last_attack_landed[i]).unwrap_or(Attack::NONE),
+                ));
+            }
+            continue;
+        }
+
+        let row = event.as_mut().unwrap();
+        row.duration += 1;
+
+        // ------------------------------------ finalize event ----------------------------------- //
+        let outcome = if opnt_frames.just_lost_stock(i) || opnt_frames.dying(i) {
+            Some(EdgeguardOutcome::KILLED)
+        } else if opnt_grounded[i] && !opnt_offstage {
+            Some(EdgeguardOutcome::RETURNED)
+        } else {
+            None
+        };
+
+        if let Some(outcome) = outcome {
+            row.outcome = outcome;
+            row.end_percent = opnt.percent[i - 1];
+            table.push_row(row);
+            event = None;
+            continue;
+        }
+
+        // ----------------------------------- mid-event data ----------------------------------- //
+        if plyr_frames.just_entered_state(ActionState::CLIFF_CATCH, i) {
+            row.time_to_ledge.get_or_insert(row.duration);
+        }
+
+        if ledgehogging(plyr_frames, opnt_frames, stage, i)
+            && !ledgehogging(plyr_frames, opnt_frames, stage, i - 1)
+        {
+            row.options.push(EdgeguardOption::LEDGEHOG);
+        }
+
+        if plyr_frames.just_changed_state(i)
+            && ActionState::AERIAL_ATTACK_RANGE.contains(&post.action_state[i])
+            && stage.is_offstage(post.position[i])
+        {
+            row.options.push(EdgeguardOption::OFFSTAGE_AERIAL);
+        }
+
+        let frame = i as i32 - 123;
+        if projectile_frames.contains(&frame) && !projectile_frames.contains(&(frame - 1)) {
+            row.options.push(EdgeguardOption::PROJECTILE);
+        }
+
+        if opnt_frames.just_changed_state(i)
+            && matches!(
+                ActionState::from_repr(opnt.action_state[i]),
+                Some(
+                    ActionState::FLY_REFLECT_WALL
+                        | ActionState::FLY_REFLECT_CEIL
+                        | ActionState::PASSIVE_WALL
+                        | ActionState::PASSIVE_WALL_JUMP
+                        | ActionState::PASSIVE_CEIL
+                )
+            )
+        {
+            row.options.push(EdgeguardOption::STAGE_SPIKE);
+        }
+    }
+
+    table
+}
+
+/// Returns true if the player is holding the ledge while the opponent is within `LEDGE_GRAB_RANGE`
+/// of it
+fn ledgehogging(plyr_frames: &Frames, opnt_frames: &Frames, stage: &Stage, i: usize) -> bool {
+    let plyr_pos = plyr_frames.post.position[i];
+    if plyr_frames.post.action_state[i] != ActionState::CLIFF_WAIT as u16 {
+        return false;
+    }
+
+    let ledge = stage.ledges[(plyr_pos.x > 0.0) as usize];
+    opnt_frames.post.position[i].distance(Position::new(ledge.x, ledge.y)) <= LEDGE_GRAB_RANGE
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct EdgeguardStats {
+    pub frame_index: Vec<i32>,
+    pub opnt_stocks: Vec<u8>,
+    pub start_percent: Vec<f32>,
+    pub end_percent: Vec<f32>,
+    pub sent_offstage_by: Vec<Attack>,
+    pub options: Vec<Vec<EdgeguardOption>>,
+    pub time_to_ledge: Vec<Option<u32>>,
+    pub duration: Vec<u32>,
+    pub outcome: Vec<EdgeguardOutcome>,
+}
+
+impl EdgeguardStats {
+    pub fn push_row(&mut self, row: &EdgeguardRow) {
+        self.frame_index.push(row.frame_index);
+        self.opnt_stocks.push(row.opnt_stocks);
+        self.start_percent.push(row.start_percent);
+        self.end_percent.push(row.end_percent);
+        self.sent_offstage_by.push(row.sent_offstage_by);
+        self.options.push(row.options.clone());
+        self.time_to_ledge.push(row.time_to_ledge);
+        self.duration.push(row.duration);
+        self.outcome.push(row.outcome);
+    }
+}
+
+impl Stat for EdgeguardStats {}
+
+#[derive(Debug, Clone)]
+pub struct EdgeguardRow {
+    pub frame_index: i32,
+    pub opnt_stocks: u8,
+    /// Opponent's percent when they were sent offstage
+    pub start_percent: f32,
+    /// Opponent's percent when they died or made it back to the stage
+    pub end_percent: f32,
+    pub sent_offstage_by: Attack,
+    pub options: Vec<EdgeguardOption>,
+    /// Frames between the opponent being sent offstage and the player's first ledge grab
+    pub time_to_ledge: Option<u32>,
+    pub duration: u32,
+    pub outcome: EdgeguardOutcome,
+}
+
+impl EdgeguardRow {
+    pub fn new(
+        frame_index: i32,
+        opnt_stocks: u8,
+        start_percent: f32,
+        sent_offstage_by: Attack,
+    ) -> Self {
+        Self {
+            frame_index,
+            opnt_stocks,
+            start_percent,
+            end_percent: start_percent,
+            sent_offstage_by,
+            options: Vec::new(),
+            time_to_ledge: None,
+            duration: 0,
+            outcome: EdgeguardOutcome::default(),
+        }
+    }
+}
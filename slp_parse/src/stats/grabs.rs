@@ -0,0 +1,247 @@
+#![allow(non_camel_case_types)]
+
+use ssbm_utils::enums::ActionState;
+use strum_macros::{Display, EnumString, IntoStaticStr};
+
+use crate::{
+    frames::Frames,
+    stats::{conversions::CONVERSION_RESET_WINDOW, Stat},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Display, EnumString, IntoStaticStr)]
+pub enum GrabType {
+    STANDING,
+    DASH,
+    /// Jump-cancelled grab (grab out of jumpsquat)
+    JUMP_CANCEL,
+    /// Standing grab out of a dash turnaround
+    PIVOT,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Display, EnumString, IntoStaticStr)]
+pub enum ThrowDirection {
+    FORWARD,
+    BACK,
+    UP,
+    DOWN,
+}
+
+#[inline]
+fn grab_state(state: u16) -> bool {
+    (ActionState::CATCH as u16..=ActionState::THROW_LW as u16).contains(&state)
+}
+
+/// Returns true if the opponent is still in a punishable situation (being hit, held, thrown, teching,
+/// or downed)
+#[inline]
+fn opnt_punished(opnt_frames: &Frames, i: usize) -> bool {
+    opnt_frames.damaged_state(i)
+        || opnt_frames.grabbed(i)
+        || opnt_frames.cmd_grabbed(i)
+        || opnt_frames.thrown(i)
+        || opnt_frames.teching(i)
+        || opnt_frames.downed(i)
+        || opnt_frames.in_hitstun(i)
+}
+
+pub(crate) fn find_grabs(plyr_frames: &Frames, opnt_frames: &Frames) -> GrabStats {
+    let post = &plyr_frames.post;
+    let states: &[u16] = &post.action_state;
+    let opnt = &opnt_frames.post;
+    let len = plyr_frames.len();
+
+    let mut table = GrabStats::default();
+    let mut chain = 0;
+
+    for i in 1..len {
+        let grab_type = if plyr_frames.just_entered_state(ActionState::CATCH_DASH, i) {
+            GrabType::DASH
+        } else if plyr_frames.just_entered_state(ActionState::CATCH, i) {
+            let prev = states[i - 1];
+            if prev == ActionState::KNEE_BEND {
+                GrabType::JUMP_CANCEL
+            } else if prev == ActionState::TURN
+                || (prev == ActionState::DASH && post.orientation[i] != post.orientation[i - 1])
+            {
+                GrabType::PIVOT
+            } else {
+                GrabType::STANDING
+            }
+        } else {
+            continue;
+        };
+
+        let mut row = GrabRow::new(
+            i as i32 - 123,
+            post.stocks[i],
+            opnt.percent[i - 1],
+            grab_type,
+        );
+
+        // ----------------------------------------- grab ----------------------------------------- //
+        let mut j = i;
+        while j < len && grab_state(states[j]) {
+            match ActionState::from_repr(states[j]) {
+                Some(ActionState::CATCH_PULL | ActionState::CATCH_DASH_PULL) => row.success = true,
+                Some(ActionState::CATCH_ATTACK) if plyr_frames.just_changed_state(j) => {
+                    row.pummels += 1
+                }
+                Some(ActionState::THROW_F) => row.throw = Some(ThrowDirection::FORWARD),
+                Some(ActionState::THROW_B) => row.throw = Some(ThrowDirection::BACK),
+                Some(ActionState::THROW_HI) => row.throw = Some(ThrowDirection::UP),
+                Some(ActionState::THROW_LW) => row.throw = Some(ThrowDirection::DOWN),
+                _ => (),
+            }
+            j += 1;
+        }
+
+        if !row.success {
+            chain = 0;
+            table.push_row(&row);
+            continue;
+        }
+
+        chain += 1;
+        row.chaingrab = chain;
+
+        // --------------------------------------- follow-up -------------------------------------- //
+        let mut end = j.min(len - 1);
+
+        if row.throw.is_some() {
+            let mut reset_counter = 0;
+            let mut tech_start = None;
+            let mut k = j;
+
+            while k < len {
+                if opnt_frames.just_lost_stock(k) {
+                    break;
+                }
+
+                if tech_start.is_none() && (opnt_frames.teching(k) || opnt_frames.downed(k)) {
+                    tech_start = Some(k);
+                    row.techchase = Some(false);
+                }
+
+                // the next grab gets its own row
+                let regrabbed = (plyr_frames.just_entered_state(ActionState::CATCH, k)
+                    || plyr_frames.just_entered_state(ActionState::CATCH_DASH, k))
+                    && (k..len)
+                        .take_while(|&x| grab_state(states[x]))
+                        .any(|x| opnt_frames.grabbed(x));
+
+                if regrabbed {
+                    row.regrab = true;
+                }
+
+                if tech_start.is_some() && (regrabbed || opnt_frames.just_took_damage(k)) {
+                    row.techchase = Some(true);
+                }
+
+                if regrabbed {
+                    break;
+                }
+
+                if opnt_punished(opnt_frames, k) {
+                    reset_counter = 0;
+                } else {
+                    reset_counter += 1;
+                    if reset_counter > CONVERSION_RESET_WINDOW {
+                        break;
+                    }
+                }
+
+                k += 1;
+            }
+
+            end = k.min(len - 1);
+        }
+
+        if !row.regrab {
+            chain = 0;
+        }
+
+        row.damage = opnt.percent[end] - row.opnt_percent;
+        // percent resets to 0 on death
+        if opnt_frames.just_lost_stock(end) {
+            row.damage = opnt.percent[end - 1] - row.opnt_percent;
+        }
+
+        table.push_row(&row);
+    }
+
+    table
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct GrabStats {
+    pub frame_index: Vec<i32>,
+    pub stocks: Vec<u8>,
+    pub opnt_percent: Vec<f32>,
+    pub grab_type: Vec<GrabType>,
+    pub success: Vec<bool>,
+    pub pummels: Vec<u32>,
+    pub throw: Vec<Option<ThrowDirection>>,
+    pub techchase: Vec<Option<bool>>,
+    pub regrab: Vec<bool>,
+    pub chaingrab: Vec<u32>,
+    pub damage: Vec<f32>,
+}
+
+impl GrabStats {
+    pub fn push_row(&mut self, row: &GrabRow) {
+        self.frame_index.push(row.frame_index);
+        self.stocks.push(row.stocks);
+        self.opnt_percent.push(row.opnt_percent);
+        self.grab_type.push(row.grab_type);
+        self.success.push(row.success);
+        self.pummels.push(row.pummels);
+        self.throw.push(row.throw);
+        self.techchase.push(row.techchase);
+        self.regrab.push(row.regrab);
+        self.chaingrab.push(row.chaingrab);
+        self.damage.push(row.damage);
+    }
+}
+
+impl Stat for GrabStats {}
+
+#[derive(Debug, Clone)]
+pub struct GrabRow {
+    pub frame_index: i32,
+    pub stocks: u8,
+    /// Opponent's percent when the grab started
+    pub opnt_percent: f32,
+    pub grab_type: GrabType,
+    pub success: bool,
+    pub pummels: u32,
+    /// None if the grab whiffed or the opponent escaped
+    pub throw: Option<ThrowDirection>,
+    /// None if the opponent never teched or missed a tech after the throw. Otherwise, whether the
+    /// player hit or regrabbed them afterwards
+    pub techchase: Option<bool>,
+    /// The player grabbed the opponent again before neutral was reset
+    pub regrab: bool,
+    /// Position of this grab in a chain of regrabs, starting at 1. 0 for whiffs
+    pub chaingrab: u32,
+    /// Damage dealt between the grab starting and either the next regrab or the opponent returning
+    /// to neutral (`CONVERSION_RESET_WINDOW` frames out of hitstun, tech, etc.)
+    pub damage: f32,
+}
+
+impl GrabRow {
+    pub fn new(frame_index: i32, stocks: u8, opnt_percent: f32, grab_type: GrabType) -> Self {
+        Self {
+            frame_index,
+            stocks,
+            opnt_percent,
+            grab_type,
+            success: false,
+            pummels: 0,
+            throw: None,
+            techchase: None,
+            regrab: false,
+            chaingrab: 0,
+            damage: 0.0,
+        }
+    }
+}
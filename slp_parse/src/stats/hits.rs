@@ -0,0 +1,268 @@
+#![allow(non_camel_case_types)]
+
+use std::collections::HashMap;
+
+use ssbm_utils::{
+    enums::{Attack, Item, Port},
+    types::Velocity,
+};
+use strum_macros::{Display, EnumString, IntoStaticStr};
+
+use crate::{events::item_frames::ItemFrames, frames::Frames, player::Player, stats::Stat};
+
+/// The entity that landed a hit
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Display, EnumString, IntoStaticStr)]
+pub enum HitSource {
+    CHARACTER,
+    /// Ice Climbers' Nana
+    NANA,
+    /// An item or projectile owned by the attacker
+    ITEM,
+}
+
+/// Maps (frame index, instance id) to the row in `ItemFrames` that had that instance id on that
+/// frame. Empty if the replay doesn't contain instance ids (< 3.16.0)
+pub(crate) fn item_instances(items: Option<&ItemFrames>) -> HashMap<(i32, u16), usize> {
+    let Some(items) = items else {
+        return HashMap::new();
+    };
+    let Some(instance_ids) = items.instance_id.as_ref() else {
+        return HashMap::new();
+    };
+
+    (0..items.len())
+        .map(|i| ((items.frame_index[i], instance_ids[i]), i))
+        .collect()
+}
+
+/// Returns the `ItemFrames` row of the item with the given instance id on frame `i` (0-indexed).
+/// Projectiles are often destroyed on hit, so the previous frame is also checked
+pub(crate) fn find_item_instance(
+    instances: &HashMap<(i32, u16), usize>,
+    instance_id: u16,
+    i: usize,
+) -> Option<usize> {
+    let frame = i as i32 - 123;
+    instances
+        .get(&(frame, instance_id))
+        .or_else(|| instances.get(&(frame - 1, instance_id)))
+        .copied()
+}
+
+/// Returns the item in the given `ItemFrames` row, resolving turnip faces and missile types when
+/// they're available
+pub(crate) fn resolve_item(items: &ItemFrames, row: usize) -> Item {
+    let item = Item::from_repr(items.item_id[row]).unwrap_or(Item::UNKNOWN);
+    match item {
+        Item::PEACH_TURNIP => items.turnip_type.as_ref().map(|x| x[row]),
+        Item::SAMUS_MISSILE => items.missile_type.as_ref().map(|x| x[row]),
+        _ => None,
+    }
+    .map_or(item, |subitem| item.resolve_subitem(subitem))
+}
+
+/// Returns true if the hit the victim took on frame `i` came from the given character. Always false
+/// if the replay doesn't contain instance ids (< 3.16.0)
+pub(crate) fn hit_by(victim: &Frames, attacker: &Frames, i: usize) -> bool {
+    let (Some(hit_by), Some(instance_id)) = (
+        victim.post.instance_hit_by.as_ref(),
+        attacker.post.instance_id.as_ref(),
+    ) else {
+        return false;
+    };
+
+    hit_by[i] == instance_id[i]
+}
+
+/// Returns the frames of whichever of the opponent or the opponent's Nana most recently hit the
+/// victim, as of frame `i`. Falls back to the opponent if the opponent isn't Ice Climbers, the
+/// victim hasn't been hit yet, or the replay doesn't contain instance ids (< 3.16.0)
+pub(crate) fn last_attacker<'a>(
+    victim: &Frames,
+    opnt_frames: &'a Frames,
+    opnt_nana: Option<&'a Frames>,
+    i: usize,
+) -> &'a Frames {
+    let Some(nana) = opnt_nana else {
+        return opnt_frames;
+    };
+
+    (1..=i)
+        .rev()
+        .find(|&k| victim.just_took_damage(k))
+        .filter(|&k| hit_by(victim, nana, k))
+        .map_or(opnt_frames, |_| nana)
+}
+
+/// Finds every hit landed by the player, their Nana, or any item they own, on the opponent or the
+/// opponent's Nana. Hits are attributed by matching the victim's `instance_hit_by` against the
+/// instance ids of each entity, so damage from stage hazards or the victim's own items is excluded.
+///
+/// Requires replay version 3.16.0
+pub(crate) fn find_hits(
+    player: &Player,
+    opponent: &Player,
+    items: Option<&ItemFrames>,
+) -> HitStats {
+    let instances = item_instances(items);
+    let owner = player.port as i8;
+
+    let victims = std::iter::once((&opponent.frames, false))
+        .chain(opponent.nana_frames.as_ref().map(|nana| (nana, true)));
+
+    let mut table = HitStats::default();
+
+    for (victim, victim_nana) in victims {
+        let (Some(hit_by_id), Some(knockback)) = (
+            victim.post.instance_hit_by.as_ref(),
+            victim.post.knockback.as_ref(),
+        ) else {
+            continue;
+        };
+
+        for i in 1..victim.len() {
+            if !victim.just_took_damage(i) {
+                continue;
+            }
+
+            let nana = player
+                .nana_frames
+                .as_ref()
+                .filter(|&x| hit_by(victim, x, i));
+
+            let (source, attacker_frames, item_row) = if hit_by(victim, &player.frames, i) {
+                (HitSource::CHARACTER, &player.frames, None)
+            } else if let Some(nana) = nana {
+                (HitSource::NANA, nana, None)
+            } else {
+                match find_item_instance(&instances, hit_by_id[i], i) {
+                    Some(row)
+                        if items
+                            .and_then(|x| x.owner.as_ref())
+                            .is_some_and(|x| x[row] == owner) =>
+                    {
+                        (HitSource::ITEM, &player.frames, Some(row))
+                    }
+                    _ => continue,
+                }
+            };
+
+            let hitlag_frames = (i..victim.len())
+                .take_while(|&k| victim.in_hitlag(k))
+                .count() as u32;
+
+            table.push_row(&HitRow {
+                frame_index: i as i32 - 123,
+                attacker: player.port,
+                source,
+                item: item_row.map(|row| resolve_item(items.unwrap(), row)),
+                spawn_id: item_row.map(|row| items.unwrap().spawn_id[row]),
+                victim: opponent.port,
+                victim_nana,
+                attack: Attack::from_repr(attacker_frames.post.last_attack_landed[i])
+                    .unwrap_or(Attack::NONE),
+                damage: victim.damage_taken(i),
+                hitlag_frames,
+                knockback: knockback[i],
+            });
+        }
+    }
+
+    table.sort_by_frame();
+    table
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct HitStats {
+    pub frame_index: Vec<i32>,
+    pub attacker: Vec<Port>,
+    pub source: Vec<HitSource>,
+    pub item: Vec<Option<Item>>,
+    pub spawn_id: Vec<Option<u32>>,
+    pub victim: Vec<Port>,
+    pub victim_nana: Vec<bool>,
+    pub attack: Vec<Attack>,
+    pub damage: Vec<f32>,
+    pub hitlag_frames: Vec<u32>,
+    pub knockback: Vec<Velocity>,
+}
+
+impl HitStats {
+    pub fn push_row(&mut self, row: &HitRow) {
+        self.frame_index.push(row.frame_index);
+        self.attacker.push(row.attacker);
+        self.source.push(row.source);
+        self.item.push(row.item);
+        self.spawn_id.push(row.spawn_id);
+        self.victim.push(row.victim);
+        self.victim_nana.push(row.victim_nana);
+        self.attack.push(row.attack);
+        self.damage.push(row.damage);
+        self.hitlag_frames.push(row.hitlag_frames);
+        self.knockback.push(row.knockback);
+    }
+
+    pub fn len(&self) -> usize {
+        self.frame_index.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.frame_index.is_empty()
+    }
+
+    pub fn get_row(&self, i: usize) -> HitRow {
+        HitRow {
+            frame_index: self.frame_index[i],
+            attacker: self.attacker[i],
+            source: self.source[i],
+            item: self.item[i],
+            spawn_id: self.spawn_id[i],
+            victim: self.victim[i],
+            victim_nana: self.victim_nana[i],
+            attack: self.attack[i],
+            damage: self.damage[i],
+            hitlag_frames: self.hitlag_frames[i],
+            knockback: self.knockback[i],
+        }
+    }
+
+    /// Returns the index of the hit landed on the given (-123 indexed) frame, if any
+    pub fn find_frame(&self, frame_index: i32) -> Option<usize> {
+        self.frame_index.binary_search(&frame_index).ok()
+    }
+
+    /// Hits on Nana are pushed after hits on Popo, so the rows are re-sorted to keep the table in
+    /// frame order
+    fn sort_by_frame(&mut self) {
+        let mut rows = (0..self.len()).map(|i| self.get_row(i)).collect::<Vec<_>>();
+        rows.sort_by_key(|x| x.frame_index);
+
+        let mut sorted = HitStats::default();
+        for row in &rows {
+            sorted.push_row(row);
+        }
+        *self = sorted;
+    }
+}
+
+impl Stat for HitStats {}
+
+#[derive(Debug, Clone)]
+pub struct HitRow {
+    pub frame_index: i32,
+    pub attacker: Port,
+    pub source: HitSource,
+    /// The item or projectile that landed the hit. Only populated for `HitSource::ITEM`
+    pub item: Option<Item>,
+    /// The spawn id of the item, which is unique per item per game
+    pub spawn_id: Option<u32>,
+    pub victim: Port,
+    /// The victim was the opponent's Nana rather than the opponent
+    pub victim_nana: bool,
+    /// The attacking character's last landed attack. For item hits this may be stale
+    pub attack: Attack,
+    pub damage: f32,
+    /// Number of frames the victim spent in hitlag, including the hit frame
+    pub hitlag_frames: u32,
+    pub knockback: Velocity,
+}
@@ -0,0 +1,145 @@
+#![allow(non_camel_case_types)]
+
+use strum_macros::{Display, EnumString, IntoStaticStr};
+
+use crate::{frames::Frames, stats::Stat};
+
+/// Nana copies Popo's inputs after a short delay. Her inputs are considered in sync if they match
+/// any of Popo's inputs from the current frame or up to this many frames before it
+pub const DESYNC_INPUT_WINDOW: usize = 3;
+/// Popo and Nana are considered separated while they're further apart than this distance
+pub const SEPARATION_DISTANCE: f32 = 30.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Display, EnumString, IntoStaticStr)]
+pub enum IcsEvent {
+    /// Nana's inputs diverged from Popo's
+    DESYNC,
+    /// Popo and Nana were further apart than `SEPARATION_DISTANCE`
+    SEPARATED,
+    /// Popo was alive without Nana
+    SOLO,
+}
+
+/// Nana's frames are filled with dummy values (percent -1) while she's inactive, i.e. after she
+/// dies and before she respawns with Popo
+#[inline]
+fn nana_alive(nana_frames: &Frames, i: usize) -> bool {
+    nana_frames.post.percent[i] >= 0.0 && !nana_frames.dying(i)
+}
+
+fn desynced(popo_frames: &Frames, nana_frames: &Frames, i: usize) -> bool {
+    let nana_input = nana_frames.pre.engine_buttons[i];
+    !(i.saturating_sub(DESYNC_INPUT_WINDOW)..=i)
+        .any(|k| popo_frames.pre.engine_buttons[k] == nana_input)
+}
+
+/// Finds every stretch of frames where the Ice Climbers were desynced, separated, or Popo was alone.
+/// A single frame can be part of several events (e.g. a desync that separates the climbers)
+pub(crate) fn find_ics(popo_frames: &Frames, nana_frames: &Frames) -> IcsStats {
+    let mut rows: Vec<IcsRow> = Vec::new();
+    // one in-progress event per event type, in the order DESYNC, SEPARATED, SOLO
+    let mut active: [Option<IcsRow>; 3] = Default::default();
+
+    for i in 1..popo_frames.len() {
+        let popo_alive = !popo_frames.dying(i);
+        let both_alive = popo_alive && nana_alive(nana_frames, i);
+        let distance = popo_frames.post.position[i].distance(nana_frames.post.position[i]);
+
+        let events = [
+            (
+                IcsEvent::DESYNC,
+                both_alive && desynced(popo_frames, nana_frames, i),
+            ),
+            (
+                IcsEvent::SEPARATED,
+                both_alive && distance > SEPARATION_DISTANCE,
+            ),
+            (IcsEvent::SOLO, popo_alive && !nana_alive(nana_frames, i)),
+        ];
+
+        for (slot, (event, ongoing)) in active.iter_mut().zip(events) {
+            match slot {
+                Some(row) if ongoing => row.duration += 1,
+                Some(_) => rows.push(slot.take().unwrap()),
+                None if ongoing => {
+                    *slot = Some(IcsRow {
+                        frame_index: i as i32 - 123,
+                        stocks: popo_frames.post.stocks[i],
+                        event,
+                        duration: 1,
+                    })
+                }
+                None => (),
+            }
+        }
+    }
+
+    rows.extend(active.into_iter().flatten());
+    rows.sort_by_key(|row| row.frame_index);
+
+    let mut table = IcsStats::default();
+    for row in &rows {
+        table.push_row(row);
+    }
+
+    table
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct IcsStats {
+    pub frame_index: Vec<i32>,
+    pub stocks: Vec<u8>,
+    pub event: Vec<IcsEvent>,
+    pub duration: Vec<u32>,
+}
+
+impl IcsStats {
+    pub fn push_row(&mut self, row: &IcsRow) {
+        self.frame_index.push(row.frame_index);
+        self.stocks.push(row.stocks);
+        self.event.push(row.event);
+        self.duration.push(row.duration);
+    }
+
+    fn total_duration(&self, event: IcsEvent) -> u32 {
+        self.event
+            .iter()
+            .zip(self.duration.iter())
+            .filter(|(&x, _)| x == event)
+            .map(|(_, &duration)| duration)
+            .sum()
+    }
+
+    /// Number of times Nana's inputs diverged from Popo's
+    pub fn desyncs(&self) -> usize {
+        self.event
+            .iter()
+            .filter(|&&x| x == IcsEvent::DESYNC)
+            .count()
+    }
+
+    pub fn frames_desynced(&self) -> u32 {
+        self.total_duration(IcsEvent::DESYNC)
+    }
+
+    pub fn frames_separated(&self) -> u32 {
+        self.total_duration(IcsEvent::SEPARATED)
+    }
+
+    /// Number of frames Popo spent alive after Nana died
+    pub fn solo_frames(&self) -> u32 {
+        self.total_duration(IcsEvent::SOLO)
+    }
+}
+
+impl Stat for IcsStats {}
+
+#[derive(Debug, Clone)]
+pub struct IcsRow {
+    /// The first frame of the event
+    pub frame_index: i32,
+    pub stocks: u8,
+    pub event: IcsEvent,
+    /// Number of frames the event lasted
+    pub duration: u32,
+}
@@ -1,34 +1,102 @@
+#![allow(non_camel_case_types)]
+
 use ssbm_utils::enums::{
     buttons::{ANYTRIGGER_MASK, CSTICK_MASK, DIGITAL_TRIGGER_MASK, JOYSTICK_MASK},
-    BitFlags, ControllerInput,
+    BitFlags, ControllerInput, StickRegion,
+};
+use strum_macros::{Display, EnumString, IntoStaticStr};
+
+use crate::{
+    frames::Frames,
+    stats::{ConversionStats, Stat},
 };
 
-use crate::{frames::Frames, stats::Stat};
-
-// #[derive(Debug, Default)]
-// struct ButtonCounts {
-//     a: u32,
-//     b: u32,
-//     x: u32,
-//     y: u32,
-//     l: u32,
-//     r: u32,
-//     z: u32,
-//     start: u32,
-//     j_up: u32,
-//     j_down: u32,
-//     j_left: u32,
-//     j_right: u32,
-//     c_up: u32,
-//     c_down: u32,
-//     c_left: u32,
-//     c_right: u32,
-//     d_up: u32,
-//     d_down: u32,
-//     d_left: u32,
-//     d_right: u32,
-// }
+/// Min joystick magnitude along either axis for a stick movement to count as a flick
+pub const FLICK_THRESHOLD: f32 = 0.8;
+/// Max number of frames the joystick can take to travel from the deadzone to `FLICK_THRESHOLD` for
+/// the movement to count as a flick
+pub const FLICK_WINDOW: usize = 2;
+
+/// What the player was doing when an input was made
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Display, EnumString, IntoStaticStr)]
+pub enum InputPhase {
+    /// Neither punishing nor being punished
+    NEUTRAL,
+    /// Mid-conversion on the opponent
+    PUNISH,
+    /// In hitstun or a damaged state
+    HITSTUN,
+}
+
+/// Number of times each controller button was pressed
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ButtonCounts {
+    pub a: u32,
+    pub b: u32,
+    pub x: u32,
+    pub y: u32,
+    pub z: u32,
+    pub l: u32,
+    pub r: u32,
+    pub start: u32,
+    pub d_up: u32,
+    pub d_down: u32,
+    pub d_left: u32,
+    pub d_right: u32,
+}
+
+impl ButtonCounts {
+    /// Adds each button in the given bitfield of newly pressed controller buttons
+    fn add(&mut self, pressed: u16) {
+        let buttons = [
+            (ControllerInput::A, &mut self.a),
+            (ControllerInput::B, &mut self.b),
+            (ControllerInput::X, &mut self.x),
+            (ControllerInput::Y, &mut self.y),
+            (ControllerInput::Z, &mut self.z),
+            (ControllerInput::L, &mut self.l),
+            (ControllerInput::R, &mut self.r),
+            (ControllerInput::START, &mut self.start),
+            (ControllerInput::DPAD_UP, &mut self.d_up),
+            (ControllerInput::DPAD_DOWN, &mut self.d_down),
+            (ControllerInput::DPAD_LEFT, &mut self.d_left),
+            (ControllerInput::DPAD_RIGHT, &mut self.d_right),
+        ];
+
+        for (button, count) in buttons {
+            if button.intersects(pressed) {
+                *count += 1;
+            }
+        }
+    }
+}
+
+/// Returns true if the joystick reached `FLICK_THRESHOLD` on frame `i`, having been in the deadzone
+/// within the last `FLICK_WINDOW` frames
+fn flicked(frames: &Frames, i: usize) -> bool {
+    let joystick = &frames.pre.joystick;
+    let tilted =
+        |k: usize| joystick[k].x.abs() >= FLICK_THRESHOLD || joystick[k].y.abs() >= FLICK_THRESHOLD;
+
+    tilted(i)
+        && !tilted(i - 1)
+        && (i.saturating_sub(FLICK_WINDOW)..i)
+            .any(|k| joystick[k].as_stickregion() == StickRegion::DEAD_ZONE)
+}
+
+/// Inputs per minute across the frames that were in the given phase. None if no frames were in
+/// that phase
+fn phase_apm(phases: &[InputPhase], input_counts: &[u32], phase: InputPhase) -> Option<f32> {
+    let (frames, inputs) = phases
+        .iter()
+        .zip(input_counts.iter())
+        .filter(|(&x, _)| x == phase)
+        .fold((0, 0), |(frames, inputs), (_, &count)| {
+            (frames + 1, inputs + count)
+        });
 
+    (frames > 0).then(|| inputs as f32 / (frames as f32 / 60.0 / 60.0))
+}
 
 #[derive(Debug, Clone, Default)]
 pub struct InputStats {
@@ -39,14 +107,65 @@ pub struct InputStats {
     pub apm: Vec<f32>,
     pub trigger_pref: Vec<ControllerInput>,
     pub jump_pref: Vec<ControllerInput>,
+    pub buttons: Vec<ButtonCounts>,
+    /// Number of joystick flicks into each region, indexed by `StickRegion as usize`
+    pub stick_flicks: Vec<[u32; 8]>,
+    pub neutral_apm: Vec<Option<f32>>,
+    pub punish_apm: Vec<Option<f32>>,
+    pub hitstun_apm: Vec<Option<f32>>,
+    /// Per-frame. True if any new input was made on that frame
+    pub new_input: Box<[bool]>,
+    /// Per-frame
+    pub phase: Box<[InputPhase]>,
+}
+
+impl InputStats {
+    /// Returns the number of joystick flicks into the given region. Always 0 for `DEAD_ZONE`, or if
+    /// the stats are empty
+    pub fn flicks(&self, region: StickRegion) -> u32 {
+        match region {
+            StickRegion::DEAD_ZONE => 0,
+            _ => self.stick_flicks.first().map_or(0, |x| x[region as usize]),
+        }
+    }
 }
 
 impl Stat for InputStats {}
 
-pub fn find_inputs(frames: &Frames, duration: usize) -> InputStats {
+/// Counts the player's inputs, both in total and per game phase. Punishes are taken from the
+/// player's conversions, so they end `CONVERSION_RESET_WINDOW` frames after the opponent leaves
+/// hitstun rather than immediately
+pub fn find_inputs(frames: &Frames, conversions: &ConversionStats, duration: usize) -> InputStats {
     let en_btn = &frames.pre.engine_buttons;
     let ctrl_btn = &frames.pre.controller_buttons;
 
+    let mut punishing = vec![false; en_btn.len()];
+    for (&start, &end) in conversions
+        .frame_index
+        .iter()
+        .zip(conversions.end_frame.iter())
+    {
+        let start = (start + 123) as usize;
+        let end = ((end + 123) as usize).min(punishing.len() - 1);
+        punishing[start..=end].fill(true);
+    }
+
+    let phase = (0..en_btn.len())
+        .map(|i| {
+            if frames.in_hitstun(i) || frames.damaged_state(i) {
+                InputPhase::HITSTUN
+            } else if punishing[i] {
+                InputPhase::PUNISH
+            } else {
+                InputPhase::NEUTRAL
+            }
+        })
+        .collect::<Box<[_]>>();
+
+    let mut buttons = ButtonCounts::default();
+    let mut stick_flicks = [0; 8];
+    let mut input_counts = vec![0; en_btn.len()];
+
     let mut digital_counts = 0;
     let mut stick_counts = 0;
     let mut cstick_counts = 0;
@@ -66,6 +185,8 @@ pub fn find_inputs(frames: &Frames, duration: usize) -> InputStats {
         let ctrl_changed = !ctrl_prev & ctrl_curr;
 
         digital_counts += ctrl_changed.count_ones();
+        input_counts[i] += ctrl_changed.count_ones();
+        buttons.add(ctrl_changed);
 
         if ControllerInput::L.intersects(ctrl_changed) {
             l_count += 1.0;
@@ -92,7 +213,15 @@ pub fn find_inputs(frames: &Frames, duration: usize) -> InputStats {
 
         if curr_stick != 0 {
             let changed = prev_stick ^ curr_stick;
-            stick_counts += changed.count_ones()
+            stick_counts += changed.count_ones();
+            input_counts[i] += changed.count_ones();
+        }
+
+        if flicked(frames, i) {
+            let region = frames.pre.joystick[i].as_stickregion();
+            if region != StickRegion::DEAD_ZONE {
+                stick_flicks[region as usize] += 1;
+            }
         }
 
         let curr_cstick = en_curr & CSTICK_MASK;
@@ -100,7 +229,8 @@ pub fn find_inputs(frames: &Frames, duration: usize) -> InputStats {
 
         if curr_cstick != 0 {
             let changed = prev_cstick ^ curr_cstick;
-            cstick_counts += changed.count_ones()
+            cstick_counts += changed.count_ones();
+            input_counts[i] += changed.count_ones();
         }
 
         // TODO fix this
@@ -111,6 +241,7 @@ pub fn find_inputs(frames: &Frames, duration: usize) -> InputStats {
         // and we didn't just start pressing Z
         {
             trigger_counts += 1;
+            input_counts[i] += 1;
         }
     }
 
@@ -158,5 +289,12 @@ pub fn find_inputs(frames: &Frames, duration: usize) -> InputStats {
             / (duration as f32 / 60.0 / 60.0)],
         trigger_pref: vec![trigger_pref],
         jump_pref: vec![jump_pref],
+        buttons: vec![buttons],
+        stick_flicks: vec![stick_flicks],
+        neutral_apm: vec![phase_apm(&phase, &input_counts, InputPhase::NEUTRAL)],
+        punish_apm: vec![phase_apm(&phase, &input_counts, InputPhase::PUNISH)],
+        hitstun_apm: vec![phase_apm(&phase, &input_counts, InputPhase::HITSTUN)],
+        new_input: input_counts.iter().map(|&x| x > 0).collect(),
+        phase,
     }
 }
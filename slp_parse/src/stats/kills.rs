@@ -0,0 +1,136 @@
+use ssbm_utils::enums::{stage::Stage, Attack};
+
+use crate::{
+    frames::Frames,
+    stats::{hits::last_attacker, Stat},
+    utils::Direction,
+};
+
+/// Non-SD deaths below this percent are flagged as early kills
+pub const EARLY_KILL_PERCENT: f32 = 60.0;
+/// Deaths above this percent are flagged as the player surviving for an unusually long time
+pub const HIGH_PERCENT_SURVIVAL: f32 = 150.0;
+/// Max number of frames before the stock is lost to search for the blast zone crossing
+const BLASTZONE_LOOKBACK: usize = 300;
+
+/// Finds every stock the player lost
+pub(crate) fn find_kills(
+    plyr_frames: &Frames,
+    opnt_frames: &Frames,
+    opnt_nana: Option<&Frames>,
+    stage: &Stage,
+) -> KillStats {
+    let post = &plyr_frames.post;
+    let grounded: &[bool] = post.is_grounded.as_ref().unwrap();
+
+    let mut table = KillStats::default();
+
+    for i in 1..plyr_frames.len() {
+        if !plyr_frames.just_lost_stock(i) {
+            continue;
+        }
+
+        // the last frame the player was inside the blast zones. The stock is usually lost on the
+        // same frame the blast zone is crossed, so the crossing frame (k + 1) can be the current
+        // frame even though the search itself stops before it
+        let crossed = (i.saturating_sub(BLASTZONE_LOOKBACK)..i)
+            .rev()
+            .find(|&k| !stage.is_past_blastzone(post.position[k]))
+            .filter(|&k| stage.is_past_blastzone(post.position[k + 1]));
+
+        let blastzone = crossed.map(|k| {
+            let pos = post.position[k + 1];
+            let bz = &stage.blastzones;
+            if pos.y >= bz.top {
+                Direction::UP
+            } else if pos.y <= bz.bottom {
+                Direction::DOWN
+            } else if pos.x <= bz.left {
+                Direction::LEFT
+            } else {
+                Direction::RIGHT
+            }
+        });
+
+        let death_frame = crossed.map_or(i, |k| k + 1);
+
+        /* A stock is self-destructed if the player wasn't hit at any point since they were last
+        safely landed on the stage. Grabbing the ledge doesn't count, so being hit offstage, making
+        it back to the ledge, and then missing the recovery is still attributed to the hit */
+        let safe = (0..death_frame)
+            .rev()
+            .find(|&k| grounded[k] && !stage.is_offstage(post.position[k]));
+        let self_destruct = !(safe.unwrap_or(0) + 1..=death_frame)
+            .any(|k| plyr_frames.just_took_damage(k) || plyr_frames.in_hitstun(k));
+
+        let percent = post.percent[i - 1];
+
+        table.push_row(&KillRow {
+            frame_index: i as i32 - 123,
+            stocks: post.stocks[i - 1],
+            percent,
+            killed_by: if self_destruct {
+                Attack::NONE
+            } else {
+                let attacker = last_attacker(plyr_frames, opnt_frames, opnt_nana, i);
+                Attack::from_repr(attacker.post.last_attack_landed[i]).unwrap_or(Attack::NONE)
+            },
+            blastzone,
+            self_destruct,
+            opnt_percent: opnt_frames.post.percent[i],
+            early_kill: !self_destruct && percent < EARLY_KILL_PERCENT,
+            survived_high_percent: percent > HIGH_PERCENT_SURVIVAL,
+        });
+    }
+
+    table
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct KillStats {
+    pub frame_index: Vec<i32>,
+    pub stocks: Vec<u8>,
+    pub percent: Vec<f32>,
+    pub killed_by: Vec<Attack>,
+    pub blastzone: Vec<Option<Direction>>,
+    pub self_destruct: Vec<bool>,
+    pub opnt_percent: Vec<f32>,
+    pub early_kill: Vec<bool>,
+    pub survived_high_percent: Vec<bool>,
+}
+
+impl KillStats {
+    pub fn push_row(&mut self, row: &KillRow) {
+        self.frame_index.push(row.frame_index);
+        self.stocks.push(row.stocks);
+        self.percent.push(row.percent);
+        self.killed_by.push(row.killed_by);
+        self.blastzone.push(row.blastzone);
+        self.self_destruct.push(row.self_destruct);
+        self.opnt_percent.push(row.opnt_percent);
+        self.early_kill.push(row.early_kill);
+        self.survived_high_percent.push(row.survived_high_percent);
+    }
+}
+
+impl Stat for KillStats {}
+
+#[derive(Debug, Clone)]
+pub struct KillRow {
+    /// The frame on which the stock counter decremented
+    pub frame_index: i32,
+    /// Stock count before the stock was lost
+    pub stocks: u8,
+    pub percent: f32,
+    /// The last attack to hit the player, from either the opponent or their Nana. `Attack::NONE` for
+    /// self-destructs
+    pub killed_by: Attack,
+    /// None if the player never crossed a blast zone (e.g. they were killed by a command grab)
+    pub blastzone: Option<Direction>,
+    pub self_destruct: bool,
+    pub opnt_percent: f32,
+    /// Died below `EARLY_KILL_PERCENT` without self-destructing
+    pub early_kill: bool,
+    /// Died above `HIGH_PERCENT_SURVIVAL`
+    pub survived_high_percent: bool,
+}
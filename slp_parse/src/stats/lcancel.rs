@@ -1,10 +1,13 @@
 #![allow(clippy::type_complexity)]
 
-use ssbm_utils::{checks::{is_fastfalling, is_in_hitlag, just_input_lcancel}, prelude::stage::GroundID};
 use ssbm_utils::enums::{stage::Stage, ActionState, Attack, LCancelState};
+use ssbm_utils::{
+    checks::{is_fastfalling, is_in_hitlag, just_input_lcancel},
+    prelude::stage::GroundID,
+};
 use std::ops::Deref;
 
-use crate::frames::Frames;
+use crate::{events::stage_events::StageEvents, frames::Frames};
 
 #[derive(Debug, Clone, Default)]
 pub struct LCancelStats {
@@ -13,13 +16,22 @@ pub struct LCancelStats {
     pub attack: Vec<Attack>,
     pub l_cancel: Vec<bool>,
     pub trigger_input_frame: Vec<Option<i32>>,
+    /// On Fountain of Dreams (>= v3.18.0), side platforms are checked against their live height. A
+    /// stale ground id resolves to `GroundID::UNKNOWN`
     pub position: Vec<GroundID>,
+    /// Live height of the platform landed on. Only populated for Fountain of Dreams side platforms
+    /// on replays >= v3.18.0
+    pub platform_height: Vec<Option<f32>>,
     pub fastfall: Vec<bool>,
     pub during_hitlag: Vec<bool>,
     pub percent: Vec<f32>,
 }
 
-pub fn find_lcancels(frames: &Frames, stage: &Stage) -> LCancelStats {
+pub fn find_lcancels(
+    frames: &Frames,
+    stage: &Stage,
+    stage_events: Option<&StageEvents>,
+) -> LCancelStats {
     let mut table = LCancelStats::default();
 
     let mut l_input_frame: Option<i32> = None;
@@ -96,7 +108,21 @@ pub fn find_lcancels(frames: &Frames, stage: &Stage) -> LCancelStats {
         table.attack.push(attack.unwrap());
         table.l_cancel.push(LCancelState::from_repr(lcancel) == Some(LCancelState::SUCCESS));
         table.trigger_input_frame.push(l_input_frame);
-        table.position.push(stage.ground_from_id(last_ground_ids[i]));
+        let ground = stage_events.map_or_else(
+            || stage.ground_from_id(last_ground_ids[i]),
+            |x| {
+                x.ground_at(
+                    stage,
+                    i as i32 - 123,
+                    last_ground_ids[i],
+                    frames.post.position[i].y,
+                )
+            },
+        );
+        table.position.push(ground);
+        table
+            .platform_height
+            .push(stage_events.and_then(|x| x.platform_height(stage, i as i32 - 123, ground)));
         table.fastfall.push(is_fastfalling(flags[i - 1]));
         table.during_hitlag.push(during_hitlag);
         table.percent.push(percents[i]);
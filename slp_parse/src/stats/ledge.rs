@@ -0,0 +1,222 @@
+#![allow(non_camel_case_types)]
+
+use ssbm_utils::{
+    enums::{stage::Stage, ActionState, Hurtbox},
+    types::Position,
+};
+use strum_macros::{Display, EnumString, IntoStaticStr};
+
+use crate::{frames::Frames, stats::Stat};
+
+/// Number of frames after leaving the ledge to look for an airdodge + landing
+pub const LEDGEDASH_WINDOW: usize = 40;
+/// Number of frames after a ledge option finishes in which getting hit counts as being punished
+pub const LEDGE_PUNISH_WINDOW: usize = 20;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Display, EnumString, IntoStaticStr)]
+pub enum LedgeOption {
+    GETUP,
+    ROLL,
+    JUMP,
+    ATTACK,
+    DROP,
+    LEDGEDASH,
+    /// Regular getup followed by a wavedash back towards the ledge
+    WAVEDASH_BACK,
+    /// Hit off of the ledge before choosing an option
+    HIT,
+}
+
+pub(crate) fn find_ledge(plyr_frames: &Frames, stage: &Stage) -> LedgeStats {
+    let post = &plyr_frames.post;
+    let states: &[u16] = &post.action_state;
+    let grounded: &[bool] = post.is_grounded.as_ref().unwrap();
+    let hurtbox = post.hurtbox_state.as_deref();
+    let len = plyr_frames.len();
+
+    let mut table = LedgeStats::default();
+
+    for i in 1..len {
+        if !plyr_frames.just_entered_state(ActionState::CLIFF_CATCH, i) {
+            continue;
+        }
+
+        // --------------------------------------- hanging -------------------------------------- //
+        let mut j = i;
+        while j < len
+            && (states[j] == ActionState::CLIFF_CATCH || states[j] == ActionState::CLIFF_WAIT)
+            && !plyr_frames.just_took_damage(j)
+        {
+            j += 1;
+        }
+
+        // game ended while hanging
+        if j >= len {
+            break;
+        }
+
+        let mut row = LedgeRow::new(
+            i as i32 - 123,
+            post.stocks[i],
+            post.percent[i],
+            post.position[i],
+            (j - i) as u32,
+        );
+
+        let state = ActionState::from_repr(states[j]);
+        row.option = if plyr_frames.just_took_damage(j) || plyr_frames.damaged_state(j) {
+            LedgeOption::HIT
+        } else {
+            match state {
+                Some(ActionState::CLIFF_CLIMB_SLOW | ActionState::CLIFF_CLIMB_QUICK) => {
+                    LedgeOption::GETUP
+                }
+                Some(ActionState::CLIFF_ATTACK_SLOW | ActionState::CLIFF_ATTACK_QUICK) => {
+                    LedgeOption::ATTACK
+                }
+                Some(ActionState::CLIFF_ESCAPE_SLOW | ActionState::CLIFF_ESCAPE_QUICK) => {
+                    LedgeOption::ROLL
+                }
+                Some(
+                    ActionState::CLIFF_JUMP_SLOW_1
+                    | ActionState::CLIFF_JUMP_SLOW_2
+                    | ActionState::CLIFF_JUMP_QUICK_1
+                    | ActionState::CLIFF_JUMP_QUICK_2,
+                ) => LedgeOption::JUMP,
+                _ => LedgeOption::DROP,
+            }
+        };
+
+        // first frame that isn't a ledge action (i.e. the end of the getup/roll/attack animation)
+        let mut option_end = j;
+        while option_end < len && plyr_frames.ledge_action(option_end) {
+            option_end += 1;
+        }
+
+        // ---------------------------------- ledgedash/wavedash ---------------------------------- //
+        let mut landing = None;
+        if matches!(
+            row.option,
+            LedgeOption::DROP | LedgeOption::JUMP | LedgeOption::GETUP
+        ) {
+            let mut airdodged = false;
+            for k in option_end..(option_end + LEDGEDASH_WINDOW).min(len) {
+                if plyr_frames.just_took_damage(k)
+                    || plyr_frames.just_entered_state(ActionState::CLIFF_CATCH, k)
+                {
+                    break;
+                }
+                if states[k] == ActionState::ESCAPE_AIR {
+                    airdodged = true;
+                }
+                // getups start grounded, so we have to wait for them to leave the ground first
+                if airdodged && grounded[k] && !stage.is_offstage(post.position[k]) {
+                    landing = Some(k);
+                    break;
+                }
+            }
+        }
+
+        if let Some(land) = landing {
+            match row.option {
+                LedgeOption::GETUP => {
+                    // wavedashing towards the ledge means moving away from the center of the stage
+                    let dist = post.position[land].x - post.position[option_end].x;
+                    if dist * row.position.x.signum() > 0.0 {
+                        row.option = LedgeOption::WAVEDASH_BACK;
+                    }
+                }
+                _ => {
+                    row.option = LedgeOption::LEDGEDASH;
+
+                    // actionable as soon as landing lag ends
+                    let actionable = (land..len)
+                        .find(|&k| states[k] != ActionState::LAND_FALL_SPECIAL)
+                        .unwrap_or(len - 1);
+                    option_end = actionable;
+
+                    if let Some(hurtbox) = hurtbox {
+                        let vulnerable = (i + 1..len)
+                            .find(|&k| hurtbox[k] == Hurtbox::VULNERABLE as u8)
+                            .unwrap_or(len - 1);
+                        row.galint = Some(vulnerable as i32 - actionable as i32);
+                    }
+                }
+            }
+        }
+
+        // -------------------------------------- punished -------------------------------------- //
+        if row.option != LedgeOption::HIT {
+            row.punished = (j + 1..(option_end + LEDGE_PUNISH_WINDOW).min(len))
+                .any(|k| plyr_frames.just_took_damage(k));
+        }
+
+        table.push_row(&row);
+    }
+
+    table
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct LedgeStats {
+    pub frame_index: Vec<i32>,
+    pub stocks: Vec<u8>,
+    pub percent: Vec<f32>,
+    pub position: Vec<Position>,
+    pub frames_on_ledge: Vec<u32>,
+    pub option: Vec<LedgeOption>,
+    pub galint: Vec<Option<i32>>,
+    pub punished: Vec<bool>,
+}
+
+impl LedgeStats {
+    pub fn push_row(&mut self, row: &LedgeRow) {
+        self.frame_index.push(row.frame_index);
+        self.stocks.push(row.stocks);
+        self.percent.push(row.percent);
+        self.position.push(row.position);
+        self.frames_on_ledge.push(row.frames_on_ledge);
+        self.option.push(row.option);
+        self.galint.push(row.galint);
+        self.punished.push(row.punished);
+    }
+}
+
+impl Stat for LedgeStats {}
+
+#[derive(Debug, Clone)]
+pub struct LedgeRow {
+    pub frame_index: i32,
+    pub stocks: u8,
+    pub percent: f32,
+    pub position: Position,
+    /// Frames spent in CLIFF_CATCH and CLIFF_WAIT before choosing an option
+    pub frames_on_ledge: u32,
+    pub option: LedgeOption,
+    /// Ground-actionable intangibility frames. Only populated for ledgedashes on replays >= 2.1.0.
+    /// Negative values are the number of frames the player was vulnerable before they were
+    /// actionable
+    pub galint: Option<i32>,
+    pub punished: bool,
+}
+
+impl LedgeRow {
+    pub fn new(
+        frame_index: i32,
+        stocks: u8,
+        percent: f32,
+        position: Position,
+        frames_on_ledge: u32,
+    ) -> Self {
+        Self {
+            frame_index,
+            stocks,
+            percent,
+            position,
+            frames_on_ledge,
+            option: LedgeOption::DROP,
+            galint: None,
+            punished: false,
+        }
+    }
+}
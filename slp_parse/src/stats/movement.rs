@@ -0,0 +1,271 @@
+#![allow(non_camel_case_types)]
+
+use ssbm_utils::enums::{
+    stage::{GroundID, Stage},
+    ActionState,
+};
+use strum_macros::{Display, EnumString, IntoStaticStr};
+
+use crate::{
+    events::game_start::ControllerFix,
+    frames::Frames,
+    player::UCFToggles,
+    stats::{shield::guarding, Stat},
+};
+
+/// Min joystick x magnitude to start a dash
+pub const DASH_THRESHOLD: f32 = 0.8;
+/// Min number of dashes in a row (i.e. 2 dashbacks) for them to count as a dash dance
+pub const DASH_DANCE_MIN_DASHES: u32 = 3;
+/// Max number of frames spent in the dashback before standing for it to count as a pivot
+pub const PIVOT_WINDOW: usize = 2;
+/// Min number of consecutive frames sliding backwards during a single dash for it to count as a
+/// moonwalk
+pub const MOONWALK_MIN_FRAMES: u32 = 5;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Display, EnumString, IntoStaticStr)]
+pub enum MovementType {
+    /// `DASH_DANCE_MIN_DASHES` or more dashes in a row, each in the opposite direction
+    DASH_DANCE,
+    /// Turning around during a dash. Successful dashbacks spend a single frame in `TURN` before
+    /// dashing the other way, failed dashbacks result in a full tilt turn instead
+    DASHBACK,
+    /// Dropping through a platform directly from shield. Failed shield drops result in a spotdodge
+    SHIELD_DROP,
+    /// Crouching directly out of a run
+    RUN_CANCEL,
+    /// Dashing back and immediately standing still, facing the new direction
+    PIVOT,
+    /// Sliding backwards during a dash
+    MOONWALK,
+}
+
+#[inline]
+fn on_platform(plyr_frames: &Frames, stage: &Stage, i: usize) -> bool {
+    plyr_frames.post.last_ground_id.as_ref().is_some_and(|x| {
+        matches!(
+            stage.ground_from_id(x[i]),
+            GroundID::LEFT_PLATFORM | GroundID::TOP_PLATFORM | GroundID::RIGHT_PLATFORM
+        )
+    })
+}
+
+/// Adds a dash dance row if the given dash start frames are long enough to count as one
+fn push_dash_dance(rows: &mut Vec<MovementRow>, plyr_frames: &Frames, dashes: &[usize]) {
+    let count = dashes.len() as u32;
+    if count < DASH_DANCE_MIN_DASHES {
+        return;
+    }
+
+    let start = dashes[0];
+    let mut row = MovementRow::new(
+        start as i32 - 123,
+        plyr_frames.post.stocks[start],
+        MovementType::DASH_DANCE,
+    );
+    row.dashes = Some(count);
+    row.cadence = Some((dashes[dashes.len() - 1] - start) as f32 / (count - 1) as f32);
+    rows.push(row);
+}
+
+/// Finds dash dances, dashbacks, shield drops, run cancels, pivots and moonwalks. Dashback and
+/// shield drop rows record whether they succeeded along with the player's controller fix toggle,
+/// so that technique errors can be told apart from controller issues.
+///
+/// Failed shield drops are detected as spotdodges out of shield while standing on a platform, which
+/// requires replay version 2.0.0
+pub(crate) fn find_movement(
+    plyr_frames: &Frames,
+    stage: &Stage,
+    ucf: Option<UCFToggles>,
+) -> MovementStats {
+    let post = &plyr_frames.post;
+    let states = &post.action_state;
+    let orientation = &post.orientation;
+    let joystick = &plyr_frames.pre.joystick;
+    let len = plyr_frames.len();
+
+    let mut rows: Vec<MovementRow> = Vec::new();
+    // start frame of each dash in the current (potential) dash dance
+    let mut dashes: Vec<usize> = Vec::new();
+    let mut backwards = 0;
+
+    for i in 1..len {
+        let state = states[i];
+        let prev = states[i - 1];
+        let new_row = |movement| MovementRow::new(i as i32 - 123, post.stocks[i], movement);
+
+        let dashing = state == ActionState::DASH;
+        // the dash that a successful dashback turns into
+        let dashback =
+            dashing && prev == ActionState::TURN && i >= 2 && states[i - 2] == ActionState::DASH;
+
+        // --------------------------------------- dashback --------------------------------------- //
+        if prev == ActionState::DASH && state == ActionState::TURN {
+            let success = states.get(i + 1).is_some_and(|&x| x == ActionState::DASH);
+            // failed attempts are only counted if the stick passed through the tilt zone on its way
+            // to a full dash input, otherwise it was just a regular turnaround
+            if success
+                || (i..(i + 3).min(len))
+                    .any(|k| joystick[k].x * orientation[i - 1] <= -DASH_THRESHOLD)
+            {
+                let mut row = new_row(MovementType::DASHBACK);
+                row.success = Some(success);
+                row.controller_fix = ucf.map(|x| x.dashback);
+                rows.push(row);
+            }
+        }
+
+        // ----------------------------------------- pivot ---------------------------------------- //
+        if state == ActionState::WAIT
+            && prev == ActionState::DASH
+            && dashes.len() >= 2
+            && dashes.last().is_some_and(|&x| i - x <= PIVOT_WINDOW)
+        {
+            rows.push(new_row(MovementType::PIVOT));
+        }
+
+        // -------------------------------------- dash dance -------------------------------------- //
+        if dashing && (prev != ActionState::DASH || dashback) {
+            if !dashback {
+                push_dash_dance(&mut rows, plyr_frames, &dashes);
+                dashes.clear();
+            }
+            dashes.push(i);
+            backwards = 0;
+        } else if !dashing && state != ActionState::TURN && !dashes.is_empty() {
+            push_dash_dance(&mut rows, plyr_frames, &dashes);
+            dashes.clear();
+        }
+
+        // --------------------------------------- moonwalk --------------------------------------- //
+        if dashing && (post.position[i].x - post.position[i - 1].x) * orientation[i] < 0.0 {
+            backwards += 1;
+            if backwards == MOONWALK_MIN_FRAMES {
+                let start = i + 1 - MOONWALK_MIN_FRAMES as usize;
+                rows.push(MovementRow::new(
+                    start as i32 - 123,
+                    post.stocks[start],
+                    MovementType::MOONWALK,
+                ));
+            }
+        } else {
+            backwards = 0;
+        }
+
+        // -------------------------------------- run cancel -------------------------------------- //
+        if prev == ActionState::RUN && state == ActionState::SQUAT {
+            rows.push(new_row(MovementType::RUN_CANCEL));
+        }
+
+        // -------------------------------------- shield drop ------------------------------------- //
+        if guarding(prev) {
+            let success = if state == ActionState::PASS {
+                Some(true)
+            } else if state == ActionState::ESCAPE && on_platform(plyr_frames, stage, i - 1) {
+                Some(false)
+            } else {
+                None
+            };
+
+            if success.is_some() {
+                let mut row = new_row(MovementType::SHIELD_DROP);
+                row.success = success;
+                row.controller_fix = ucf.map(|x| x.shield_drop);
+                rows.push(row);
+            }
+        }
+    }
+
+    push_dash_dance(&mut rows, plyr_frames, &dashes);
+
+    // dash dances are only added once they end
+    rows.sort_by_key(|x| x.frame_index);
+
+    let mut table = MovementStats::default();
+    for row in &rows {
+        table.push_row(row);
+    }
+
+    table
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct MovementStats {
+    pub frame_index: Vec<i32>,
+    pub stocks: Vec<u8>,
+    pub movement: Vec<MovementType>,
+    pub success: Vec<Option<bool>>,
+    pub controller_fix: Vec<Option<ControllerFix>>,
+    pub dashes: Vec<Option<u32>>,
+    pub cadence: Vec<Option<f32>>,
+}
+
+impl MovementStats {
+    pub fn push_row(&mut self, row: &MovementRow) {
+        self.frame_index.push(row.frame_index);
+        self.stocks.push(row.stocks);
+        self.movement.push(row.movement);
+        self.success.push(row.success);
+        self.controller_fix.push(row.controller_fix);
+        self.dashes.push(row.dashes);
+        self.cadence.push(row.cadence);
+    }
+
+    pub fn count(&self, movement: MovementType) -> usize {
+        self.movement.iter().filter(|&&x| x == movement).count()
+    }
+
+    /// Ratio of successful attempts of the given movement. Only dashbacks and shield drops track
+    /// success, so other movement types always return None
+    pub fn success_rate(&self, movement: MovementType) -> Option<f32> {
+        let attempts = self
+            .movement
+            .iter()
+            .zip(self.success.iter())
+            .filter_map(|(&x, &success)| (x == movement).then_some(success?))
+            .collect::<Vec<_>>();
+
+        (!attempts.is_empty())
+            .then(|| attempts.iter().filter(|&&x| x).count() as f32 / attempts.len() as f32)
+    }
+
+    /// Average number of frames per dash across all dash dances
+    pub fn avg_cadence(&self) -> Option<f32> {
+        let cadence = self.cadence.iter().flatten().collect::<Vec<_>>();
+        (!cadence.is_empty()).then(|| cadence.iter().copied().sum::<f32>() / cadence.len() as f32)
+    }
+}
+
+impl Stat for MovementStats {}
+
+#[derive(Debug, Clone)]
+pub struct MovementRow {
+    pub frame_index: i32,
+    pub stocks: u8,
+    pub movement: MovementType,
+    /// Whether the dashback or shield drop succeeded. None for other movement types
+    pub success: Option<bool>,
+    /// The player's dashback or shield drop toggle, depending on the movement type. None for other
+    /// movement types, or if the replay doesn't contain UCF toggles (< 1.0.0)
+    pub controller_fix: Option<ControllerFix>,
+    /// Number of dashes in the dash dance. None for other movement types
+    pub dashes: Option<u32>,
+    /// Average number of frames between each direction change in the dash dance. None for other
+    /// movement types
+    pub cadence: Option<f32>,
+}
+
+impl MovementRow {
+    pub fn new(frame_index: i32, stocks: u8, movement: MovementType) -> Self {
+        Self {
+            frame_index,
+            stocks,
+            movement,
+            success: None,
+            controller_fix: None,
+            dashes: None,
+            cadence: None,
+        }
+    }
+}
@@ -0,0 +1,189 @@
+use std::collections::BTreeMap;
+
+use ssbm_utils::{calc::unstaled_damage, enums::Attack};
+
+use crate::{
+    frames::{state_to_attack, Frames},
+    stats::{powershield::shield_hit, Stat},
+};
+
+#[derive(Debug, Default, Clone, Copy)]
+struct MoveCounter {
+    started: u32,
+    hit: u32,
+    shielded: u32,
+    damaging_hits: u32,
+    total_damage: f32,
+    unstaled_damage: f32,
+    staleness: f32,
+    /// The outcome of the most recent use of the move, which hasn't been counted yet
+    pending: Option<Outcome>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Outcome {
+    Whiff,
+    Shielded,
+    Hit,
+}
+
+impl MoveCounter {
+    fn resolve(&mut self) {
+        match self.pending.take() {
+            Some(Outcome::Hit) => self.hit += 1,
+            Some(Outcome::Shielded) => self.shielded += 1,
+            _ => (),
+        }
+    }
+
+    fn mark(&mut self, outcome: Outcome) {
+        // a hit outranks a shield hit, e.g. a multihit move that pokes through shield
+        if let Some(pending) = self.pending.as_mut() {
+            *pending = (*pending).max(outcome);
+        }
+    }
+}
+
+/// Counts every use of each attack by the player, and how each one turned out. Hits are attributed to
+/// the most recent use of the player's last landed attack, so projectiles that connect after the
+/// player has moved on are still counted. `last_attack_landed` isn't updated by shield hits, so
+/// those are attributed to the attack the player is performing, and are skipped if there isn't one
+pub(crate) fn find_moves(plyr_frames: &Frames, opnt_frames: &Frames) -> MoveStats {
+    let states = &plyr_frames.post.action_state;
+    let attacks = &plyr_frames.post.last_attack_landed;
+
+    let mut counters: BTreeMap<Attack, MoveCounter> = BTreeMap::new();
+
+    for i in 1..plyr_frames.len() {
+        // ------------------------------------ move started ------------------------------------ //
+        let started = if plyr_frames.just_changed_state(i) {
+            state_to_attack(states[i]).or_else(|| plyr_frames.special_started(i))
+        } else {
+            None
+        };
+
+        if let Some(attack) = started {
+            let counter = counters.entry(attack).or_default();
+            counter.resolve();
+            counter.started += 1;
+            counter.pending = Some(Outcome::Whiff);
+        }
+
+        // -------------------------------------- move hit -------------------------------------- //
+        let hit = opnt_frames.just_took_damage(i);
+        let shielded = shield_hit(opnt_frames, i);
+
+        if !(hit || shielded) {
+            continue;
+        }
+
+        let attack = if hit {
+            Attack::from_repr(attacks[i])
+        } else {
+            plyr_frames.current_attack(i)
+        };
+        let Some(attack) = attack else {
+            continue;
+        };
+        if attack == Attack::NONE {
+            continue;
+        }
+
+        let counter = counters.entry(attack).or_default();
+        if hit {
+            counter.mark(Outcome::Hit);
+            counter.damaging_hits += 1;
+            let damage = opnt_frames.damage_taken(i);
+            counter.total_damage += damage;
+            counter.unstaled_damage += unstaled_damage(damage, &plyr_frames.stale_slots(attack, i));
+            counter.staleness += plyr_frames.staleness(attack, i);
+        } else {
+            counter.mark(Outcome::Shielded);
+        }
+    }
+
+    let mut table = MoveStats::default();
+
+    for (attack, mut counter) in counters {
+        counter.resolve();
+        let per_hit =
+            |x: f32| (counter.damaging_hits > 0).then(|| x / counter.damaging_hits as f32);
+        table.push_row(&MoveRow {
+            attack,
+            started: counter.started,
+            hit: counter.hit,
+            shielded: counter.shielded,
+            whiffed: counter
+                .started
+                .saturating_sub(counter.hit + counter.shielded),
+            total_damage: counter.total_damage,
+            avg_damage: per_hit(counter.total_damage),
+            avg_unstaled_damage: per_hit(counter.unstaled_damage),
+            avg_staleness: per_hit(counter.staleness),
+        });
+    }
+
+    table
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct MoveStats {
+    pub attack: Vec<Attack>,
+    pub started: Vec<u32>,
+    pub hit: Vec<u32>,
+    pub shielded: Vec<u32>,
+    pub whiffed: Vec<u32>,
+    pub total_damage: Vec<f32>,
+    pub avg_damage: Vec<Option<f32>>,
+    pub avg_unstaled_damage: Vec<Option<f32>>,
+    pub avg_staleness: Vec<Option<f32>>,
+}
+
+impl MoveStats {
+    pub fn push_row(&mut self, row: &MoveRow) {
+        self.attack.push(row.attack);
+        self.started.push(row.started);
+        self.hit.push(row.hit);
+        self.shielded.push(row.shielded);
+        self.whiffed.push(row.whiffed);
+        self.total_damage.push(row.total_damage);
+        self.avg_damage.push(row.avg_damage);
+        self.avg_unstaled_damage.push(row.avg_unstaled_damage);
+        self.avg_staleness.push(row.avg_staleness);
+    }
+
+    /// Returns the row index of the given attack, if the player used or landed it
+    pub fn find(&self, attack: Attack) -> Option<usize> {
+        self.attack.iter().position(|&x| x == attack)
+    }
+
+    /// Ratio of uses of the attack that hit the opponent. Returns None if the attack was never
+    /// started
+    pub fn hit_rate(&self, attack: Attack) -> Option<f32> {
+        let i = self.find(attack)?;
+        (self.started[i] > 0).then(|| self.hit[i] as f32 / self.started[i] as f32)
+    }
+}
+
+impl Stat for MoveStats {}
+
+#[derive(Debug, Clone)]
+pub struct MoveRow {
+    pub attack: Attack,
+    /// Number of times the player entered the move's action state(s)
+    pub started: u32,
+    /// Number of uses that hit the opponent at least once
+    pub hit: u32,
+    /// Number of uses that hit the opponent's shield without ever hitting the opponent
+    pub shielded: u32,
+    pub whiffed: u32,
+    /// Sum of the damage dealt by every hit of the move. Multihit moves count every hit
+    pub total_damage: f32,
+    /// Average (staled) damage per hit. None if the move never dealt damage
+    pub avg_damage: Option<f32>,
+    /// Average damage per hit before stale move negation. None if the move never dealt damage
+    pub avg_unstaled_damage: Option<f32>,
+    /// Average fraction of the move's damage removed by stale move negation. None if the move never
+    /// dealt damage
+    pub avg_staleness: Option<f32>,
+}
@@ -0,0 +1,197 @@
+#![allow(non_camel_case_types)]
+
+use std::collections::HashMap;
+
+use ssbm_utils::enums::{ActionState, Attack, BitFlags, EngineInput, Flags, Port};
+use strum_macros::{Display, EnumString, IntoStaticStr};
+
+use crate::{events::item_frames::ItemFrames, frames::Frames, stats::Stat};
+
+/// Max number of frames before entering shield to look for the trigger press
+const TRIGGER_LOOKBACK: usize = 10;
+/// Shield health lost on the hit frame below which the hit is considered powershielded. Regular
+/// shield hits always deal at least 0.7 shield damage, and natural shield decay is ~0.28 per frame
+const POWERSHIELD_DAMAGE_TOLERANCE: f32 = 0.5;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Display, EnumString, IntoStaticStr)]
+pub enum PowershieldType {
+    /// The attacker was put into hitlag by the powershield
+    PHYSICAL,
+    /// The attacker was not put into hitlag, or a projectile was reflected
+    PROJECTILE,
+}
+
+/// Returns true if the character can act out of the state (standing, moving, jumping, falling, or
+/// shielding)
+#[inline]
+fn free_state(state: u16) -> bool {
+    (ActionState::WAIT as u16..=ActionState::FALL_AERIAL_B as u16).contains(&state)
+        || (ActionState::SQUAT as u16..=ActionState::LAND as u16).contains(&state)
+        || (ActionState::GUARD_ON as u16..=ActionState::GUARD as u16).contains(&state)
+}
+
+/// Returns true if the player's shield was hit on this frame
+#[inline]
+pub(crate) fn shield_hit(plyr_frames: &Frames, i: usize) -> bool {
+    ActionState::GUARD_RANGE.contains(&plyr_frames.post.action_state[i])
+        && plyr_frames.in_hitlag(i)
+        && !plyr_frames.in_hitlag(i - 1)
+}
+
+/// Returns true if the shield hit on this frame was powershielded. Powershielded hits deal no
+/// shield damage and only occur while the powershield bubble is active.
+///
+/// Powershields still put the player into GUARD_SET_OFF, so the action state alone can't be used.
+#[inline]
+pub(crate) fn is_powershield(plyr_frames: &Frames, i: usize) -> bool {
+    let post = &plyr_frames.post;
+    post.flags
+        .as_ref()
+        .is_some_and(|x| Flags::POWERSHIELD_BUBBLE.contained_by(x[i]))
+        && post.shield_health[i - 1] - post.shield_health[i] < POWERSHIELD_DAMAGE_TOLERANCE
+}
+
+pub(crate) fn find_powershields(
+    plyr_frames: &Frames,
+    opnt_frames: &Frames,
+    port: Port,
+    items: Option<&ItemFrames>,
+) -> PowershieldStats {
+    let post = &plyr_frames.post;
+    let states: &[u16] = &post.action_state;
+    let opnt_states: &[u16] = &opnt_frames.post.action_state;
+    let len = plyr_frames.len();
+
+    // reflected projectiles turn around, and change owners on versions that record them. Mapped to
+    // 0-indexed frames
+    let mut reflects: Vec<usize> = match items {
+        Some(items) => {
+            let owner = items.owner.as_ref();
+
+            // every item active on a frame gets its own row, so each item's rows are grouped
+            // together before comparing adjacent frames
+            let mut rows: HashMap<u32, Vec<usize>> = HashMap::new();
+            for j in 0..items.len() {
+                rows.entry(items.spawn_id[j]).or_default().push(j);
+            }
+
+            rows.values()
+                .flat_map(|x| x.windows(2))
+                .filter(|w| {
+                    let (prev, curr) = (w[0], w[1]);
+                    let was_opponents = owner.is_none_or(|x| x[prev] != port as i8);
+                    let now_ours = owner.is_some_and(|x| x[curr] == port as i8);
+                    let turned = items.velocity[prev].x * items.velocity[curr].x < 0.0;
+                    was_opponents && (now_ours || turned)
+                })
+                .map(|w| (items.frame_index[w[1]] + 123) as usize)
+                .collect()
+        }
+        None => Vec::new(),
+    };
+    reflects.sort_unstable();
+    reflects.dedup();
+
+    let mut table = PowershieldStats::default();
+
+    for i in 1..len {
+        let physical = shield_hit(plyr_frames, i) && is_powershield(plyr_frames, i);
+        let reflect = reflects.binary_search(&i).is_ok()
+            && post
+                .flags
+                .as_ref()
+                .is_some_and(|x| Flags::POWERSHIELD_BUBBLE.contained_by(x[i]));
+
+        if !(physical || reflect) {
+            continue;
+        }
+
+        let ps_type = if physical && opnt_frames.in_hitlag(i) {
+            PowershieldType::PHYSICAL
+        } else {
+            PowershieldType::PROJECTILE
+        };
+
+        // ---------------------------------------- timing ---------------------------------------- //
+        let shield_start = (0..i)
+            .rev()
+            .find(|&k| !ActionState::GUARD_RANGE.contains(&states[k]))
+            .map(|k| k + 1);
+
+        // the press can come a few frames before the shield if the player was busy (e.g. landing)
+        let trigger_press = shield_start.and_then(|start| {
+            (start.saturating_sub(TRIGGER_LOOKBACK).max(1)..=i)
+                .rev()
+                .find(|&k| plyr_frames.just_pressed_any(EngineInput::ANY_TRIGGER, k))
+        });
+
+        // -------------------------------------- advantage --------------------------------------- //
+        let plyr_actionable = (i..len).find(|&k| {
+            !plyr_frames.in_hitlag(k)
+                && states[k] != ActionState::GUARD_REFLECT
+                && states[k] != ActionState::GUARD_SET_OFF
+        });
+
+        // projectiles aren't tied to the opponent's animation, so there's no meaningful advantage
+        let frame_advantage = match (ps_type, plyr_actionable) {
+            (PowershieldType::PHYSICAL, Some(plyr)) => (i + 1..len)
+                .find(|&k| free_state(opnt_states[k]) && !opnt_frames.in_hitlag(k))
+                .map(|opnt| opnt as i32 - plyr as i32),
+            _ => None,
+        };
+
+        table.push_row(&PowershieldRow {
+            frame_index: i as i32 - 123,
+            stocks: post.stocks[i],
+            attack: opnt_frames.current_attack(i).unwrap_or(Attack::NONE),
+            ps_type,
+            shield_frame: shield_start.map(|k| (i - k) as u32),
+            trigger_frame: trigger_press.map(|k| (i - k) as u32),
+            frame_advantage,
+        });
+    }
+
+    table
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct PowershieldStats {
+    pub frame_index: Vec<i32>,
+    pub stocks: Vec<u8>,
+    pub attack: Vec<Attack>,
+    pub ps_type: Vec<PowershieldType>,
+    pub shield_frame: Vec<Option<u32>>,
+    pub trigger_frame: Vec<Option<u32>>,
+    pub frame_advantage: Vec<Option<i32>>,
+}
+
+impl PowershieldStats {
+    pub fn push_row(&mut self, row: &PowershieldRow) {
+        self.frame_index.push(row.frame_index);
+        self.stocks.push(row.stocks);
+        self.attack.push(row.attack);
+        self.ps_type.push(row.ps_type);
+        self.shield_frame.push(row.shield_frame);
+        self.trigger_frame.push(row.trigger_frame);
+        self.frame_advantage.push(row.frame_advantage);
+    }
+}
+
+impl Stat for PowershieldStats {}
+
+#[derive(Debug, Clone)]
+pub struct PowershieldRow {
+    pub frame_index: i32,
+    pub stocks: u8,
+    /// The opponent's attack, taken from their action state on the frame of the powershield.
+    /// `Attack::NONE` if they weren't attacking, e.g. a projectile fired before they moved on
+    pub attack: Attack,
+    pub ps_type: PowershieldType,
+    /// Number of frames between the shield coming up and the powershield (0 = first frame)
+    pub shield_frame: Option<u32>,
+    /// Number of frames between the trigger press and the powershield
+    pub trigger_frame: Option<u32>,
+    /// Frames between the player being able to act and the opponent being able to act. Positive
+    /// values mean the player acts first. Only populated for physical powershields
+    pub frame_advantage: Option<i32>,
+}
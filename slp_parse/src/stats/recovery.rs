@@ -1,37 +1,280 @@
-// use polars::prelude::*;
-// use ssbm_utils::{
-//     enums::{stage::Stage, Attack, Character},
-//     types::Position,
-// };
-
-// use crate::{frames::Frames, utils::Direction};
-
-// struct RecoveryRow {
-//     frame_index: i32,
-//     stocks: u8,
-//     percent: f32,
-//     last_hit_by: Attack,
-//     side: Direction,
-//     jumps_remaining: u8,
-//     up_b: bool,
-//     hitstun_end: Position,
-//     dist_from_stage: f32,
-
-//     pseudo_jump: Option<bool>,
-//     randall_involved: Option<bool>,
-// }
-
-// pub fn find_recovery(
-//     plyr_frames: &Frames,
-//     plyr_char: Character,
-//     opnt_frames: &Frames,
-//     stage: &Stage,
-// ) -> DataFrame {
-//     for i in 1..plyr_frames.len() {
-
-//     }
-
-//     DataFrame::default()
-// }
-
-// enum RecoveryOption {}
+#![allow(non_camel_case_types)]
+
+use ssbm_utils::{
+    checks::is_dying,
+    enums::{
+        stage::{GroundID, Stage},
+        ActionState, Attack, Character, StageID,
+    },
+    types::Position,
+};
+use strum_macros::{Display, EnumString, IntoStaticStr};
+
+use crate::{frames::Frames, stats::Stat, utils::Direction};
+
+/// Anything the player did to get back to the stage. Recorded in the order they happened
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Display, EnumString, IntoStaticStr)]
+pub enum RecoveryOption {
+    JUMP,
+    AIRDODGE,
+    AERIAL,
+    /// Any character-specific action state (up b, side b, etc.)
+    SPECIAL,
+    WALL_JUMP,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Display, EnumString, IntoStaticStr, Default)]
+pub enum RecoveryOutcome {
+    /// Grabbed the ledge
+    #[default]
+    LEDGE,
+    /// Landed on the stage (or a platform) without grabbing the ledge
+    STAGE,
+    /// Was hit by the opponent before making it back
+    EDGEGUARDED,
+    /// Lost a stock without being hit again (e.g. SD, missed the ledge, etc.)
+    DEATH,
+}
+
+/// Returns the special that gives the character extra height the first time it's used in the air,
+/// if they have one (Mario's cape, Yoshi's egg throw, Samus' bomb jump)
+fn pseudo_jump(character: Character) -> Option<Attack> {
+    match character {
+        Character::Mario => Some(Attack::SIDE_SPECIAL),
+        Character::Yoshi => Some(Attack::UP_SPECIAL),
+        Character::Samus => Some(Attack::DOWN_SPECIAL),
+        _ => None,
+    }
+}
+
+pub(crate) fn find_recovery(
+    plyr_frames: &Frames,
+    opnt_frames: &Frames,
+    stage: &Stage,
+) -> RecoveryStats {
+    let post = &plyr_frames.post;
+    let grounded: &[bool] = post.is_grounded.as_ref().unwrap();
+    let jumps: &[u8] = post.jumps_remaining.as_ref().unwrap();
+    let last_ground: &[u16] = post.last_ground_id.as_ref().unwrap();
+    let states: &[u16] = &post.action_state;
+    let attacks = &opnt_frames.post.last_attack_landed;
+
+    let mut event: Option<RecoveryRow> = None;
+    let mut table = RecoveryStats::default();
+
+    for i in 1..plyr_frames.len() {
+        let pos = post.position[i];
+
+        // ----------------------------------- event detection ---------------------------------- //
+        if event.is_none() {
+            if plyr_frames.in_hitstun(i - 1)
+                && !plyr_frames.in_hitstun(i)
+                && !is_dying(states[i])
+                && !grounded[i]
+                && stage.is_offstage(pos)
+            {
+                event = Some(RecoveryRow::new(
+                    i as i32 - 123,
+                    post.stocks[i],
+                    post.percent[i],
+                    Attack::from_repr(attacks[i]).unwrap_or(Attack::NONE),
+                    if pos.x < 0.0 {
+                        Direction::LEFT
+                    } else {
+                        Direction::RIGHT
+                    },
+                    jumps[i],
+                    pos,
+                    stage
+                        .ledges
+                        .iter()
+                        .map(|l| pos.distance(Position::new(l.x, l.y)))
+                        .fold(f32::MAX, f32::min),
+                    plyr_frames
+                        .character(i)
+                        .and_then(pseudo_jump)
+                        .map(|_| false),
+                    (stage.id == StageID::YOSHIS_STORY).then_some(false),
+                ));
+            }
+            continue;
+        }
+
+        let row = event.as_mut().unwrap();
+        row.duration += 1;
+
+        // ------------------------------------ finalize event ----------------------------------- //
+        let outcome = if plyr_frames.just_lost_stock(i) || is_dying(states[i]) {
+            Some(RecoveryOutcome::DEATH)
+        } else if plyr_frames.just_took_damage(i) {
+            Some(RecoveryOutcome::EDGEGUARDED)
+        } else if plyr_frames.just_entered_state(ActionState::CLIFF_CATCH, i) {
+            Some(RecoveryOutcome::LEDGE)
+        } else if grounded[i] && !stage.is_offstage(pos) {
+            Some(RecoveryOutcome::STAGE)
+        } else {
+            None
+        };
+
+        if grounded[i] && stage.ground_from_id(last_ground[i]) == GroundID::RANDALL {
+            row.randall_involved = Some(true);
+        }
+
+        if let Some(outcome) = outcome {
+            row.outcome = outcome;
+            row.stock_lost = match outcome {
+                RecoveryOutcome::DEATH => true,
+                // edgeguarded players usually die, but not always. Look ahead until they make it
+                // back to the stage or lose the stock
+                RecoveryOutcome::EDGEGUARDED => (i..plyr_frames.len())
+                    .find(|&j| {
+                        plyr_frames.just_lost_stock(j)
+                            || plyr_frames.ledge_action(j)
+                            || (grounded[j] && !stage.is_offstage(post.position[j]))
+                    })
+                    .is_some_and(|j| plyr_frames.just_lost_stock(j)),
+                _ => false,
+            };
+
+            table.push_row(row);
+            event = None;
+            continue;
+        }
+
+        // ----------------------------------- mid-event data ----------------------------------- //
+        let state = states[i];
+        let prev_state = states[i - 1];
+
+        if jumps[i] < jumps[i - 1] {
+            row.options.push(RecoveryOption::JUMP);
+        }
+
+        if !plyr_frames.just_changed_state(i) {
+            continue;
+        }
+
+        if state == ActionState::ESCAPE_AIR {
+            row.options.push(RecoveryOption::AIRDODGE);
+        } else if ActionState::AERIAL_ATTACK_RANGE.contains(&state) {
+            row.options.push(RecoveryOption::AERIAL);
+        } else if state == ActionState::PASSIVE_WALL_JUMP {
+            row.options.push(RecoveryOption::WALL_JUMP);
+        } else if state > ActionState::BARREL_CANNON_WAIT as u16
+            && prev_state <= ActionState::BARREL_CANNON_WAIT as u16
+        {
+            row.options.push(RecoveryOption::SPECIAL);
+            let special = plyr_frames.special_started(i);
+            // up b's that grab the ledge never reach special fall
+            row.up_b |= special == Some(Attack::UP_SPECIAL);
+            if let Some(used) = row.pseudo_jump.as_mut() {
+                let character = plyr_frames.character(i);
+                *used |= special.is_some() && special == character.and_then(pseudo_jump);
+            }
+        } else if plyr_frames.special_fall(i) && prev_state != ActionState::ESCAPE_AIR {
+            // airdodges also end in special fall, anything else is (almost always) an up b
+            row.up_b = true;
+        }
+    }
+
+    table
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct RecoveryStats {
+    pub frame_index: Vec<i32>,
+    pub stocks: Vec<u8>,
+    pub percent: Vec<f32>,
+    pub last_hit_by: Vec<Attack>,
+    pub side: Vec<Direction>,
+    pub jumps_remaining: Vec<u8>,
+    pub hitstun_end: Vec<Position>,
+    pub dist_from_stage: Vec<f32>,
+    pub options: Vec<Vec<RecoveryOption>>,
+    pub up_b: Vec<bool>,
+    /// None for characters without a pseudo jump
+    pub pseudo_jump: Vec<Option<bool>>,
+    pub duration: Vec<u32>,
+    pub outcome: Vec<RecoveryOutcome>,
+    pub stock_lost: Vec<bool>,
+    /// Only populated on Yoshi's Story
+    pub randall_involved: Vec<Option<bool>>,
+}
+
+impl RecoveryStats {
+    pub fn push_row(&mut self, row: &RecoveryRow) {
+        self.frame_index.push(row.frame_index);
+        self.stocks.push(row.stocks);
+        self.percent.push(row.percent);
+        self.last_hit_by.push(row.last_hit_by);
+        self.side.push(row.side);
+        self.jumps_remaining.push(row.jumps_remaining);
+        self.hitstun_end.push(row.hitstun_end);
+        self.dist_from_stage.push(row.dist_from_stage);
+        self.options.push(row.options.clone());
+        self.up_b.push(row.up_b);
+        self.pseudo_jump.push(row.pseudo_jump);
+        self.duration.push(row.duration);
+        self.outcome.push(row.outcome);
+        self.stock_lost.push(row.stock_lost);
+        self.randall_involved.push(row.randall_involved);
+    }
+}
+
+impl Stat for RecoveryStats {}
+
+#[derive(Debug, Clone)]
+pub struct RecoveryRow {
+    pub frame_index: i32,
+    pub stocks: u8,
+    pub percent: f32,
+    pub last_hit_by: Attack,
+    pub side: Direction,
+    pub jumps_remaining: u8,
+    pub hitstun_end: Position,
+    /// Distance to the nearest ledge when hitstun ended
+    pub dist_from_stage: f32,
+    pub options: Vec<RecoveryOption>,
+    pub up_b: bool,
+    /// Whether the character's pseudo jump (Mario's cape, Yoshi's egg throw, Samus' bomb jump) was
+    /// used. None for characters without one
+    pub pseudo_jump: Option<bool>,
+    /// Number of frames between hitstun ending and the outcome
+    pub duration: u32,
+    pub outcome: RecoveryOutcome,
+    pub stock_lost: bool,
+    pub randall_involved: Option<bool>,
+}
+
+impl RecoveryRow {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        frame_index: i32,
+        stocks: u8,
+        percent: f32,
+        last_hit_by: Attack,
+        side: Direction,
+        jumps_remaining: u8,
+        hitstun_end: Position,
+        dist_from_stage: f32,
+        pseudo_jump: Option<bool>,
+        randall_involved: Option<bool>,
+    ) -> Self {
+        Self {
+            frame_index,
+            stocks,
+            percent,
+            last_hit_by,
+            side,
+            jumps_remaining,
+            hitstun_end,
+            dist_from_stage,
+            options: Vec::new(),
+            up_b: false,
+            pseudo_jump,
+            duration: 0,
+            outcome: RecoveryOutcome::default(),
+            stock_lost: false,
+            randall_involved,
+        }
+    }
+}
@@ -0,0 +1,295 @@
+#![allow(non_camel_case_types)]
+
+use ssbm_utils::{
+    calc::shield_stun,
+    enums::{ActionState, Attack, Character},
+};
+use strum_macros::{Display, EnumString, IntoStaticStr};
+
+use crate::{
+    frames::Frames,
+    stats::{
+        powershield::{is_powershield, shield_hit},
+        Stat,
+    },
+};
+
+/// Number of frames after jumpsquat starts to look for the action that was jump-cancelled into
+const JUMP_CANCEL_WINDOW: usize = 10;
+
+/// The first action taken out of shield after shieldstun
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Display, EnumString, IntoStaticStr)]
+pub enum OoSOption {
+    GRAB,
+    /// Grab out of jumpsquat
+    JC_GRAB,
+    /// Up smash out of jumpsquat
+    JC_UP_SMASH,
+    /// Up b directly out of shield or out of jumpsquat
+    UP_B,
+    /// Any other special move out of jumpsquat
+    SPECIAL,
+    AERIAL,
+    WAVEDASH,
+    /// Jumped without doing anything else within `JUMP_CANCEL_WINDOW` frames
+    JUMP,
+    ROLL,
+    SPOTDODGE,
+    /// Dropped through a platform directly from shield
+    SHIELD_DROP,
+    /// Released shield normally
+    RELEASE,
+}
+
+/// Shield states, excluding shield release
+#[inline]
+pub(crate) fn guarding(state: u16) -> bool {
+    matches!(
+        ActionState::from_repr(state),
+        Some(
+            ActionState::GUARD_ON
+                | ActionState::GUARD
+                | ActionState::GUARD_SET_OFF
+                | ActionState::GUARD_REFLECT
+        )
+    )
+}
+
+/// Inverts `calc::shield_damage` to get the (floored) damage of the move that hit the shield
+fn damage_from_shield(shield_damage: f32, analog: f32) -> f32 {
+    let analog_scalar = 0.2 * (1.0 - (analog - 0.3) / 0.7);
+    (shield_damage / (analog_scalar + 0.7)).round()
+}
+
+fn get_oos_option(plyr_frames: &Frames, start: usize) -> Option<OoSOption> {
+    let states = &plyr_frames.post.action_state;
+    let joystick = &plyr_frames.pre.joystick;
+
+    let special = |k: usize| states[k] > ActionState::BARREL_CANNON_WAIT as u16;
+
+    let option = match ActionState::from_repr(states[start]) {
+        Some(ActionState::CATCH) => OoSOption::GRAB,
+        Some(ActionState::ESCAPE_F | ActionState::ESCAPE_B) => OoSOption::ROLL,
+        Some(ActionState::ESCAPE) => OoSOption::SPOTDODGE,
+        Some(ActionState::PASS) => OoSOption::SHIELD_DROP,
+        Some(ActionState::GUARD_OFF) => OoSOption::RELEASE,
+        Some(ActionState::KNEE_BEND) => {
+            let end = (start + JUMP_CANCEL_WINDOW).min(plyr_frames.len());
+            let mut changes = (start + 1..end).filter(|&k| plyr_frames.just_changed_state(k));
+
+            // grounded actions cancel jumpsquat directly, so they're only checked against the
+            // state right after it
+            let Some(next) = changes.next() else {
+                return Some(OoSOption::JUMP);
+            };
+            match ActionState::from_repr(states[next]) {
+                Some(ActionState::CATCH) => return Some(OoSOption::JC_GRAB),
+                Some(ActionState::ATTACK_HI_4) => return Some(OoSOption::JC_UP_SMASH),
+                _ => (),
+            }
+
+            std::iter::once(next)
+                .chain(changes)
+                .find_map(|k| {
+                    if states[k] == ActionState::ESCAPE_AIR {
+                        Some(OoSOption::WAVEDASH)
+                    } else if ActionState::AERIAL_ATTACK_RANGE.contains(&states[k]) {
+                        Some(OoSOption::AERIAL)
+                    } else if special(k) && joystick[k].y > 0.0 {
+                        Some(OoSOption::UP_B)
+                    } else if special(k) {
+                        Some(OoSOption::SPECIAL)
+                    } else {
+                        None
+                    }
+                })
+                .unwrap_or(OoSOption::JUMP)
+        }
+        _ if special(start) => OoSOption::UP_B,
+        _ => return None,
+    };
+
+    Some(option)
+}
+
+pub(crate) fn find_shield(
+    plyr_frames: &Frames,
+    opnt_frames: &Frames,
+    player_char: Character,
+) -> ShieldStats {
+    let pre = &plyr_frames.pre;
+    let post = &plyr_frames.post;
+    let states: &[u16] = &post.action_state;
+    let attacks = &opnt_frames.post.last_attack_landed;
+    let len = plyr_frames.len();
+
+    let mut table = ShieldStats::default();
+
+    for i in 1..len {
+        // ----------------------------------- event detection ---------------------------------- //
+        let poked = guarding(states[i - 1]) && plyr_frames.just_took_damage(i);
+        let blocked = shield_hit(plyr_frames, i);
+
+        if !(poked || blocked) {
+            continue;
+        }
+
+        // last_attack_landed isn't updated by shield hits, so the attack is taken from the
+        // opponent's state. Hits that poke through shield do update it, which covers projectiles
+        let attack = opnt_frames
+            .current_attack(i)
+            .or_else(|| Attack::from_repr(attacks[i]).filter(|_| poked))
+            .unwrap_or(Attack::NONE);
+
+        let mut row = ShieldRow::new(
+            i as i32 - 123,
+            post.stocks[i],
+            attack,
+            post.shield_health[i - 1],
+        );
+
+        if poked {
+            row.poked = true;
+            table.push_row(&row);
+            continue;
+        }
+
+        let hitlag_end = (i..len)
+            .find(|&k| !plyr_frames.in_hitlag(k))
+            .unwrap_or(len - 1);
+
+        row.powershield = is_powershield(plyr_frames, i);
+        row.shield_damage = (post.shield_health[i - 1] - post.shield_health[hitlag_end]).max(0.0);
+
+        // ---------------------------------------- oos ---------------------------------------- //
+        // the first frame the player was no longer in shield, stopping early if they were hit again
+        let exit = (hitlag_end..len).find(|&k| {
+            !guarding(states[k]) || shield_hit(plyr_frames, k) || plyr_frames.just_took_damage(k)
+        });
+
+        let Some(exit) = exit else {
+            table.push_row(&row);
+            continue;
+        };
+
+        if plyr_frames.shield_broken(exit) {
+            row.broken = true;
+        } else if !guarding(states[exit]) && !plyr_frames.just_took_damage(exit) {
+            row.oos_option = get_oos_option(plyr_frames, exit);
+
+            // powershields have their own (much shorter) stun, so the state change is used instead
+            let stun_end = if row.powershield {
+                (hitlag_end..=exit)
+                    .find(|&k| states[k] != ActionState::GUARD_SET_OFF)
+                    .unwrap_or(exit)
+            } else {
+                let analog = pre.engine_trigger[i].clamp(0.3, 1.0);
+                let damage = damage_from_shield(row.shield_damage, analog);
+                hitlag_end + shield_stun(damage, analog, player_char == Character::Yoshi) as usize
+            };
+
+            if row.oos_option.is_some() {
+                row.oos_delay = Some(exit as i32 - stun_end as i32);
+            }
+        }
+
+        table.push_row(&row);
+    }
+
+    table
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct ShieldStats {
+    pub frame_index: Vec<i32>,
+    pub stocks: Vec<u8>,
+    pub attack: Vec<Attack>,
+    pub shield_health: Vec<f32>,
+    pub shield_damage: Vec<f32>,
+    pub powershield: Vec<bool>,
+    pub poked: Vec<bool>,
+    pub broken: Vec<bool>,
+    pub oos_option: Vec<Option<OoSOption>>,
+    pub oos_delay: Vec<Option<i32>>,
+}
+
+impl ShieldStats {
+    pub fn push_row(&mut self, row: &ShieldRow) {
+        self.frame_index.push(row.frame_index);
+        self.stocks.push(row.stocks);
+        self.attack.push(row.attack);
+        self.shield_health.push(row.shield_health);
+        self.shield_damage.push(row.shield_damage);
+        self.powershield.push(row.powershield);
+        self.poked.push(row.poked);
+        self.broken.push(row.broken);
+        self.oos_option.push(row.oos_option);
+        self.oos_delay.push(row.oos_delay);
+    }
+
+    pub fn hits_blocked(&self) -> usize {
+        self.poked.iter().filter(|&&x| !x).count()
+    }
+
+    pub fn shield_pokes(&self) -> usize {
+        self.poked.iter().filter(|&&x| x).count()
+    }
+
+    pub fn shield_breaks(&self) -> usize {
+        self.broken.iter().filter(|&&x| x).count()
+    }
+
+    pub fn powershields(&self) -> usize {
+        self.powershield.iter().filter(|&&x| x).count()
+    }
+
+    /// Only counts shield drops out of shieldstun, since rows are only recorded when a hit lands on
+    /// shield. Shield drops from an idle shield aren't included
+    pub fn shield_drops(&self) -> usize {
+        self.oos_option
+            .iter()
+            .filter(|&&x| x == Some(OoSOption::SHIELD_DROP))
+            .count()
+    }
+}
+
+impl Stat for ShieldStats {}
+
+#[derive(Debug, Clone)]
+pub struct ShieldRow {
+    pub frame_index: i32,
+    pub stocks: u8,
+    /// The opponent's attack, taken from their action state on the frame of the hit. `Attack::NONE`
+    /// if they weren't attacking, e.g. a projectile that hit after they moved on
+    pub attack: Attack,
+    /// Shield health before the hit
+    pub shield_health: f32,
+    pub shield_damage: f32,
+    pub powershield: bool,
+    /// Hit through the shield. Poked rows have no shield damage or OoS data
+    pub poked: bool,
+    pub broken: bool,
+    /// None if the player was hit again before acting, or if the action wasn't a recognized OoS
+    /// option
+    pub oos_option: Option<OoSOption>,
+    /// Frames between shieldstun ending and the OoS option starting. Calculated from
+    /// `calc::shield_stun` using the shield damage and trigger analog value
+    pub oos_delay: Option<i32>,
+}
+
+impl ShieldRow {
+    pub fn new(frame_index: i32, stocks: u8, attack: Attack, shield_health: f32) -> Self {
+        Self {
+            frame_index,
+            stocks,
+            attack,
+            shield_health,
+            shield_damage: 0.0,
+            powershield: false,
+            poked: false,
+            broken: false,
+            oos_option: None,
+            oos_delay: None,
+        }
+    }
+}
@@ -8,14 +8,19 @@ use ssbm_utils::{
     types::Position,
 };
 
-use crate::frames::Frames;
-
-pub fn find_techs(plyr_frames: &Frames, opnt_frames: &Frames, stage: &Stage) -> TechStats {
+use crate::{events::stage_events::StageEvents, frames::Frames, stats::hits::last_attacker};
+
+pub fn find_techs(
+    plyr_frames: &Frames,
+    opnt_frames: &Frames,
+    opnt_nana: Option<&Frames>,
+    stage: &Stage,
+    stage_events: Option<&StageEvents>,
+) -> TechStats {
     let pre = &plyr_frames.pre;
     let post = &plyr_frames.post;
     let flags = post.flags.as_ref().unwrap();
     let last_ground = post.last_ground_id.as_ref().unwrap();
-    let attacks = &opnt_frames.post.last_attack_landed;
     let opnt_pos = &opnt_frames.post.position;
 
     let mut event: Option<TechRow> = None;
@@ -63,14 +68,24 @@ pub fn find_techs(plyr_frames: &Frames, opnt_frames: &Frames, stage: &Stage) ->
             }
 
             let most_recent_input = lockout.frames_since_input();
+            let ground = stage_events.map_or_else(
+                || stage.ground_from_id(last_ground[i]),
+                |x| x.ground_at(stage, i as i32 - 123, last_ground[i], post.position[i].y),
+            );
             event = Some(TechRow::new(
                 i as i32 - 123,
                 post.stocks[i],
                 post.percent[i],
                 tech_type,
                 post.position[i],
-                stage.ground_from_id(last_ground[i]),
-                Attack::from_repr(attacks[i]).unwrap(),
+                ground,
+                stage_events.and_then(|x| x.platform_height(stage, i as i32 - 123, ground)),
+                Attack::from_repr(
+                    last_attacker(plyr_frames, opnt_frames, opnt_nana, i)
+                        .post
+                        .last_attack_landed[i],
+                )
+                .unwrap(),
                 post.position[i].distance(opnt_frames.post.position[i]),
                 (-40..=0)
                     .contains(&most_recent_input)
@@ -132,6 +147,7 @@ pub struct TechRow {
     pub punished: bool,
     pub position: Position,
     pub location: GroundID,
+    pub platform_height: Option<f32>,
     pub missed_tech: bool,
     pub towards_center: Option<bool>,
     pub towards_opponent: Option<bool>,
@@ -152,6 +168,7 @@ impl TechRow {
         tech_type: TechType,
         position: Position,
         ground_id: GroundID,
+        platform_height: Option<f32>,
         last_hit_by: Attack,
         opnt_distance: f32,
         input_frame: Option<i32>,
@@ -170,6 +187,7 @@ impl TechRow {
             punished: false,
             position,
             location: ground_id,
+            platform_height,
             missed_tech,
             towards_center: None,
             towards_opponent: None,
@@ -192,7 +210,12 @@ pub struct TechStats {
     pub tech_type: Vec<TechType>,
     pub punished: Vec<bool>,
     pub position: Vec<Position>,
+    /// On Fountain of Dreams (>= v3.18.0), side platforms are checked against their live height. A
+    /// stale ground id resolves to `GroundID::UNKNOWN`
     pub location: Vec<GroundID>,
+    /// Live height of the platform the tech happened on. Only populated for Fountain of Dreams
+    /// side platforms on replays >= v3.18.0
+    pub platform_height: Vec<Option<f32>>,
     pub missed_tech: Vec<bool>,
     pub lockout: Vec<bool>,
     pub towards_center: Vec<Option<bool>>,
@@ -213,6 +236,7 @@ impl TechStats {
         self.punished.push(stat.punished);
         self.position.push(stat.position);
         self.location.push(stat.location);
+        self.platform_height.push(stat.platform_height);
         self.missed_tech.push(stat.missed_tech);
         self.lockout.push(stat.lockout);
         self.towards_center.push(stat.towards_center);
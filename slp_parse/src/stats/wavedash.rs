@@ -1,9 +1,21 @@
 use ssbm_utils::{
-    enums::ActionState,
+    enums::{
+        stage::{GroundID, Stage},
+        ActionState,
+    },
     types::{Position, StickPos},
 };
 
-use crate::{frames::Frames, utils::Direction};
+use crate::{frames::Frames, stats::shield::guarding, utils::Direction};
+
+/// The shallowest airdodge angle (in degrees below horizontal) that still travels downward. This is
+/// a full-magnitude stick input with the y value just outside of the deadzone (-0.2875)
+pub const PERFECT_WAVEDASH_ANGLE: f32 = 16.71;
+/// Airdodges within this many degrees of `PERFECT_WAVEDASH_ANGLE` are considered perfect. Stick
+/// values are quantized, so an exact match is rarely possible
+pub const PERFECT_ANGLE_TOLERANCE: f32 = 1.0;
+/// Max number of frames before landing to search for the airdodge
+const AIRDODGE_LOOKBACK: usize = 20;
 
 #[derive(Debug, Clone, Default)]
 pub struct WavedashStats {
@@ -12,9 +24,55 @@ pub struct WavedashStats {
     pub direction: Vec<Direction>,
     pub start_position: Vec<Position>,
     pub waveland: Vec<bool>,
+    /// Frames between leaving jumpsquat and starting the airdodge. 0 is frame-perfect. None for
+    /// wavelands
+    pub airdodge_delay: Vec<Option<u32>>,
+    /// Degrees below horizontal of the airdodge, minus `PERFECT_WAVEDASH_ANGLE`. Measured from the
+    /// joystick on the frame the airdodge started
+    pub angle_deviation: Vec<f32>,
+    /// Horizontal distance travelled between the start of the wavedash (jumpsquat, or the airdodge
+    /// for wavelands) and the first actionable frame after landing
+    pub distance: Vec<f32>,
+    /// Requires replay version 2.0.0
+    pub ground: Vec<Option<GroundID>>,
+    /// Jumped directly out of shield
+    pub out_of_shield: Vec<bool>,
 }
 
-pub fn find_wavedashes(frames: &Frames) -> WavedashStats {
+impl WavedashStats {
+    pub fn len(&self) -> usize {
+        self.frame_index.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.frame_index.is_empty()
+    }
+
+    /// Ratio of wavedashes and wavelands whose angle was within `PERFECT_ANGLE_TOLERANCE` of
+    /// `PERFECT_WAVEDASH_ANGLE`. None if there were no wavedashes
+    pub fn perfect_angle_percent(&self) -> Option<f32> {
+        (!self.is_empty()).then(|| {
+            self.angle_deviation
+                .iter()
+                .filter(|x| x.abs() <= PERFECT_ANGLE_TOLERANCE)
+                .count() as f32
+                / self.len() as f32
+        })
+    }
+
+    /// Average jumpsquat-to-airdodge delay, excluding wavelands. None if there were no wavedashes
+    pub fn avg_airdodge_delay(&self) -> Option<f32> {
+        let delays = self.airdodge_delay.iter().flatten().collect::<Vec<_>>();
+        (!delays.is_empty())
+            .then(|| delays.iter().map(|&&x| x as f32).sum::<f32>() / delays.len() as f32)
+    }
+
+    pub fn avg_distance(&self) -> Option<f32> {
+        (!self.is_empty()).then(|| self.distance.iter().sum::<f32>() / self.len() as f32)
+    }
+}
+
+pub fn find_wavedashes(frames: &Frames, stage: &Stage) -> WavedashStats {
     let pre = &frames.pre;
     let post = &frames.post;
 
@@ -43,11 +101,58 @@ pub fn find_wavedashes(frames: &Frames) -> WavedashStats {
         }
 
         let (angle, direction) = degrees_below_horizontal(pre.joystick[i]);
+
+        // the airdodge is usually entered on the frame before landing, but wavelands can airdodge
+        // from much higher up
+        let airdodge = (i - AIRDODGE_LOOKBACK..i)
+            .rev()
+            .find(|&k| frames.just_entered_state(ActionState::ESCAPE_AIR, k))
+            .unwrap_or(i - 1);
+
+        let jumpsquat_end = (!waveland)
+            .then(|| {
+                (airdodge.saturating_sub(AIRDODGE_LOOKBACK)..airdodge)
+                    .rev()
+                    .find(|&k| state_frames[k] == ActionState::KNEE_BEND)
+            })
+            .flatten();
+        let jumpsquat_start = jumpsquat_end.map(|end| {
+            (0..=end)
+                .rev()
+                .find(|&k| state_frames[k] != ActionState::KNEE_BEND)
+                .map_or(0, |k| k + 1)
+        });
+
+        let start = jumpsquat_start.unwrap_or(airdodge);
+        let actionable = (i..frames.len())
+            .find(|&k| state_frames[k] != ActionState::LAND_FALL_SPECIAL)
+            .unwrap_or(frames.len() - 1);
+
+        let stick = pre.joystick[airdodge];
+        let airdodge_angle = (-stick.y).atan2(stick.x.abs()).to_degrees();
+
         wavedashes.frame_index.push(i as i32 - 123);
         wavedashes.angle.push(angle);
         wavedashes.direction.push(direction);
         wavedashes.start_position.push(post.position[i]);
         wavedashes.waveland.push(waveland);
+        wavedashes
+            .airdodge_delay
+            .push(jumpsquat_end.map(|end| (airdodge - end - 1) as u32));
+        wavedashes
+            .angle_deviation
+            .push(airdodge_angle - PERFECT_WAVEDASH_ANGLE);
+        wavedashes
+            .distance
+            .push((post.position[actionable].x - post.position[start].x).abs());
+        wavedashes.ground.push(
+            post.last_ground_id
+                .as_ref()
+                .map(|x| stage.ground_from_id(x[i])),
+        );
+        wavedashes.out_of_shield.push(
+            jumpsquat_start.is_some_and(|start| start > 0 && guarding(state_frames[start - 1])),
+        );
     }
 
     wavedashes
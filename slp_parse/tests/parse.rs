@@ -12,6 +12,7 @@ use slp_parse::{
     },
     frames::Frame,
     player::UCFToggles,
+    utils::Direction,
 };
 use ssbm_utils::{
     enums::{character::Costume, Character, ControllerInput, EngineInput, Flags, StageID},
@@ -455,3 +456,299 @@ pub fn test_frames() {
         )
     );
 }
+
+#[test]
+pub fn test_sets() {
+    let replays = [
+        test_data_path(r"test_replays\defense.slp"),
+        test_data_path(r"test_replays\inputs.slp"),
+        test_data_path(r"test_replays\netplay_sample.slp"),
+    ];
+
+    let games = replays
+        .iter()
+        .map(|x| Game::new(x, false).unwrap())
+        .collect::<Vec<_>>();
+    let stubs = replays
+        .iter()
+        .map(|x| Game::stub(x).unwrap())
+        .collect::<Vec<_>>();
+
+    // P1 LRAS'd, so P2 wins even though the GameEnd placements list P1 as the winner
+    assert_eq!(games[0].winner(), Some(Port::P2));
+    for (game, stub) in zip(&games, &stubs) {
+        assert_eq!(game.game_winner(), stub.game_winner());
+    }
+
+    let game_sets = group_sets(games);
+    let stub_sets = group_sets(stubs);
+
+    assert_eq!(game_sets.len(), stub_sets.len());
+    for (game_set, stub_set) in zip(&game_sets, &stub_sets) {
+        assert_eq!(game_set.players, stub_set.players);
+        assert_eq!(game_set.score(), stub_set.score());
+        assert_eq!(game_set.picks(), stub_set.picks());
+    }
+}
+
+#[test]
+pub fn test_dedup() {
+    let replay = test_data_path(r"test_replays\defense.slp");
+    let game = Game::new(&replay, false).unwrap();
+    let copy = Game::new(&replay, false).unwrap();
+    let shorter = Game::new(&test_data_path(r"test_replays\inputs.slp"), false).unwrap();
+
+    assert_eq!(game.first_desync(&copy), SyncStatus::Synced);
+
+    let stub = Game::stub(&replay).unwrap();
+    assert_eq!(stub.first_desync(&stub), SyncStatus::NoFrameData);
+
+    // hashed ids are persisted, so they must never change between builds
+    assert_eq!(game.identity(), GameId::Hashed(2546772297738354517));
+    assert_eq!(stub.identity(), GameId::Hashed(17296204488349520003));
+
+    let (kept, removed) = dedup(vec![game, copy]);
+    assert_eq!(kept.len(), 1);
+    assert_eq!(removed.len(), 1);
+    assert_eq!(removed[0].desync, SyncStatus::Synced);
+
+    // the shorter replay ends 470 frames early, which counts as a divergence on its own
+    let report = compare_replays(&kept[0], &shorter).unwrap();
+    assert_eq!(report.first_divergence, Some(-39));
+    assert_eq!(report.total_frames, (1923, 1453));
+    let ended_early = report
+        .divergences
+        .iter()
+        .filter(|x| matches!(x.column, slp_parse::columns::PostFrame::FrameIndex))
+        .collect::<Vec<_>>();
+    assert_eq!(ended_early.len(), 2);
+    assert_eq!(ended_early[0].frame_index, 1330);
+    assert_eq!(ended_early[0].frame_count, 470);
+}
+
+#[test]
+pub fn test_stale_queue() {
+    let replay = test_data_path(r"test_replays\netplay_sample.slp");
+    let game = Game::new(&replay, false).unwrap();
+    let (p1, p2) = (&game.players[0].frames, &game.players[1].frames);
+
+    // P1's third laser in a row. The previous two fill the most recent slots, which remove 9% and
+    // 8% of the laser's 3% damage
+    assert_eq!(p1.stale_queue[1330][..2], [Attack::NEUTRAL_SPECIAL; 2]);
+    assert!((p1.staleness(Attack::NEUTRAL_SPECIAL, 1331) - 0.17).abs() < 1e-5);
+    assert!((p2.damage_taken(1331) - 3.0 * 0.83).abs() < 1e-3);
+
+    // P1's shine sits in the 7th and 9th slots, which only remove 3% and 1% of its 8% damage
+    assert_eq!(
+        p1.stale_slots(Attack::DOWN_SPECIAL, 3486),
+        [false, false, false, false, false, false, true, false, true]
+    );
+    assert!((p2.damage_taken(3486) - 8.0 * 0.96).abs() < 1e-3);
+}
+
+#[test]
+pub fn test_recovery() {
+    let replay = test_data_path(r"test_replays\netplay_sample.slp");
+    let game = Game::new(&replay, true).unwrap();
+    let p1 = game.players[0].stats.recovery.as_ref().unwrap();
+    let p2 = game.players[1].stats.recovery.as_ref().unwrap();
+
+    assert_eq!(p1.frame_index, [521, 551, 568, 2678, 6559]);
+    assert_eq!(
+        p1.outcome,
+        [
+            RecoveryOutcome::EDGEGUARDED,
+            RecoveryOutcome::EDGEGUARDED,
+            RecoveryOutcome::DEATH,
+            RecoveryOutcome::EDGEGUARDED,
+            RecoveryOutcome::EDGEGUARDED,
+        ]
+    );
+    assert!(matches!(p1.side[3], Direction::RIGHT));
+    assert_eq!(p1.stock_lost.iter().filter(|&&x| x).count(), 3);
+
+    assert_eq!(p2.frame_index.len(), 9);
+    assert_eq!(p2.outcome[0], RecoveryOutcome::LEDGE);
+    assert_eq!(p2.outcome[3], RecoveryOutcome::STAGE);
+    assert_eq!(p2.outcome[8], RecoveryOutcome::DEATH);
+    // firebird to ledge never reaches special fall
+    assert_eq!(
+        p2.options[0],
+        [RecoveryOption::JUMP, RecoveryOption::SPECIAL]
+    );
+    assert!(p2.up_b[0]);
+    assert_eq!(
+        p2.options[6],
+        [
+            RecoveryOption::JUMP,
+            RecoveryOption::WALL_JUMP,
+            RecoveryOption::SPECIAL,
+        ]
+    );
+    assert!(!p2.up_b[6]);
+
+    // falco has no pseudo jump
+    assert!(p1.pseudo_jump.iter().all(Option::is_none));
+    assert!(p2.pseudo_jump.iter().all(Option::is_none));
+}
+
+#[test]
+pub fn test_edgeguard() {
+    let replay = test_data_path(r"test_replays\netplay_sample.slp");
+    let game = Game::new(&replay, true).unwrap();
+    let p1 = game.players[0].stats.edgeguard.as_ref().unwrap();
+    let p2 = game.players[1].stats.edgeguard.as_ref().unwrap();
+
+    assert_eq!(p1.frame_index.len(), 14);
+    assert_eq!(
+        p1.outcome
+            .iter()
+            .filter(|&&x| x == EdgeguardOutcome::KILLED)
+            .count(),
+        4
+    );
+    assert_eq!(
+        p1.options[9],
+        [
+            EdgeguardOption::PROJECTILE,
+            EdgeguardOption::OFFSTAGE_AERIAL
+        ]
+    );
+    assert_eq!(
+        p1.options[13],
+        [EdgeguardOption::STAGE_SPIKE, EdgeguardOption::PROJECTILE]
+    );
+
+    assert_eq!(
+        p2.frame_index,
+        [487, 2166, 2540, 4050, 4451, 6509, 7262, 7456]
+    );
+    assert_eq!(
+        p2.outcome,
+        [
+            EdgeguardOutcome::KILLED,
+            EdgeguardOutcome::RETURNED,
+            EdgeguardOutcome::RETURNED,
+            EdgeguardOutcome::RETURNED,
+            EdgeguardOutcome::KILLED,
+            EdgeguardOutcome::RETURNED,
+            EdgeguardOutcome::RETURNED,
+            EdgeguardOutcome::KILLED,
+        ]
+    );
+    assert_eq!(
+        p2.options[5],
+        [EdgeguardOption::PROJECTILE, EdgeguardOption::LEDGEHOG]
+    );
+}
+
+#[test]
+pub fn test_ledge() {
+    let replay = test_data_path(r"test_replays\netplay_sample.slp");
+    let game = Game::new(&replay, true).unwrap();
+    let p1 = game.players[0].stats.ledge.as_ref().unwrap();
+    let p2 = game.players[1].stats.ledge.as_ref().unwrap();
+
+    assert_eq!(p1.frame_index, [2925, 3062, 3782, 3963, 6951]);
+    assert_eq!(
+        p1.option,
+        [
+            LedgeOption::JUMP,
+            LedgeOption::DROP,
+            LedgeOption::DROP,
+            LedgeOption::DROP,
+            LedgeOption::DROP,
+        ]
+    );
+
+    assert_eq!(p2.frame_index.len(), 9);
+    assert_eq!(p2.option[0], LedgeOption::ROLL);
+    assert_eq!(p2.option[8], LedgeOption::ROLL);
+    assert_eq!(
+        p2.option
+            .iter()
+            .filter(|&&x| x == LedgeOption::DROP)
+            .count(),
+        7
+    );
+
+    // nothing in this replay is a GALINT or gets punished
+    assert!(p1.galint.iter().chain(&p2.galint).all(|x| x.is_none()));
+    assert!(!p1.punished.iter().chain(&p2.punished).any(|&x| x));
+}
+
+#[test]
+pub fn test_shield() {
+    let replay = test_data_path(r"test_replays\netplay_sample.slp");
+    let game = Game::new(&replay, true).unwrap();
+    let p1 = game.players[0].stats.shield.as_ref().unwrap();
+    let p2 = game.players[1].stats.shield.as_ref().unwrap();
+
+    assert_eq!(p1.frame_index.len(), 19);
+    assert_eq!(p1.hits_blocked(), 19);
+    assert_eq!(p1.shield_pokes(), 0);
+    assert_eq!(p1.shield_breaks(), 0);
+    assert_eq!(p1.powershields(), 2);
+    assert_eq!(p1.shield_drops(), 1);
+    assert_eq!(p1.frame_index[2], 457);
+    assert_eq!(p1.attack[2], Attack::DOWN_SPECIAL);
+    assert_eq!(p1.oos_option[2], Some(OoSOption::JUMP));
+    assert_eq!(p1.oos_delay[2], Some(2));
+    assert_eq!(p1.oos_option[11], Some(OoSOption::GRAB));
+
+    assert_eq!(p2.frame_index.len(), 14);
+    assert_eq!(p2.hits_blocked(), 13);
+    assert_eq!(p2.shield_pokes(), 1);
+    assert_eq!(p2.shield_drops(), 2);
+    assert_eq!(p2.attack[3], Attack::U_TILT);
+    assert_eq!(p2.oos_option[3], Some(OoSOption::SHIELD_DROP));
+}
+
+#[test]
+pub fn test_powershield() {
+    let replay = test_data_path(r"test_replays\netplay_sample.slp");
+    let game = Game::new(&replay, true).unwrap();
+    let p1 = game.players[0].stats.powershield.as_ref().unwrap();
+    let p2 = game.players[1].stats.powershield.as_ref().unwrap();
+
+    assert_eq!(p1.frame_index, [1033, 8992]);
+    assert_eq!(p1.attack, [Attack::NAIR, Attack::DAIR]);
+    assert_eq!(p1.ps_type, [PowershieldType::PHYSICAL; 2]);
+    assert_eq!(p1.shield_frame, [Some(2), Some(0)]);
+    assert_eq!(p1.trigger_frame, [Some(2), Some(0)]);
+    assert_eq!(p1.frame_advantage, [Some(11), Some(7)]);
+
+    assert!(p2.frame_index.is_empty());
+}
+
+#[test]
+pub fn test_moves() {
+    let replay = test_data_path(r"test_replays\netplay_sample.slp");
+    let game = Game::new(&replay, true).unwrap();
+
+    let row = |port: usize, attack: Attack| {
+        let moves = game.players[port].stats.moves.as_ref().unwrap();
+        let i = moves.attack.iter().position(|&x| x == attack).unwrap();
+        (
+            moves.started[i],
+            moves.hit[i],
+            moves.shielded[i],
+            moves.whiffed[i],
+        )
+    };
+
+    assert_eq!(row(0, Attack::DAIR), (28, 10, 3, 15));
+    assert_eq!(row(0, Attack::DOWN_SPECIAL), (25, 12, 3, 10));
+    assert_eq!(row(0, Attack::UP_SPECIAL), (4, 0, 0, 4));
+    assert_eq!(row(1, Attack::NEUTRAL_SPECIAL), (40, 15, 2, 23));
+    assert_eq!(row(1, Attack::NAIR), (8, 2, 4, 2));
+
+    let p1 = game.players[0].stats.moves.as_ref().unwrap();
+    assert!(!p1.attack.contains(&Attack::DASH_ATTACK));
+    let f_smash = p1
+        .attack
+        .iter()
+        .position(|&x| x == Attack::F_SMASH)
+        .unwrap();
+    assert_eq!(p1.avg_staleness[f_smash], None);
+}
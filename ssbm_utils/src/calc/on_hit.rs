@@ -91,7 +91,7 @@ pub fn shield_pushback_attacker(damage: f32, analog: f32) -> f32 {
 
 /// Calculates staled damage based on a damage value and stale move queue.
 ///
-/// Stale move queue is assumed to be in order from **least** recent to **most** recent. Each `true` represents an
+/// Stale move queue is assumed to be in order from **most** recent to **least** recent. Each `true` represents an
 /// instance of the move in the queue at that position. **Move order in the stale queue is very important**
 pub fn staled_damage(damage: f32, stale_queue: &[bool]) -> f32 {
     assert_eq!(stale_queue.len(), 9);
@@ -111,7 +111,7 @@ pub fn staled_damage(damage: f32, stale_queue: &[bool]) -> f32 {
 
 /// Calculates unstaled damage based on damage dealt and stale move queue.
 ///
-/// Stale move queue is assumed to be in order from **least** recent to **most** recent. Each `true` represents an
+/// Stale move queue is assumed to be in order from **most** recent to **least** recent. Each `true` represents an
 /// instance of the move in the queue at that position. **Move order in the stale queue is very important**
 pub fn unstaled_damage(damage: f32, stale_queue: &[bool]) -> f32 {
     assert_eq!(stale_queue.len(), 9);
@@ -191,5 +191,14 @@ mod test {
         );
 
         assert_eq!(usd, 15.0);
+
+        // the most recent slot removes 9% of the move's damage, the least recent removes 1%
+        let mut queue = [false; 9];
+        queue[0] = true;
+        assert_relative_eq!(staled_damage(10.0, &queue), 9.1);
+
+        let mut queue = [false; 9];
+        queue[8] = true;
+        assert_relative_eq!(staled_damage(10.0, &queue), 9.9);
     }
 }
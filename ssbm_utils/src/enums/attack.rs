@@ -2,6 +2,7 @@
 #![allow(clippy::upper_case_acronyms)]
 #![allow(non_upper_case_globals)]
 
+use serde::{Deserialize, Serialize};
 use strum_macros::{Display, EnumString, IntoStaticStr, FromRepr};
 
 
@@ -20,7 +21,9 @@ use strum_macros::{Display, EnumString, IntoStaticStr, FromRepr};
     EnumString,
     Display,
     IntoStaticStr,
-    Default
+    Default,
+    Serialize,
+    Deserialize
 )]
 #[repr(u8)]
 pub enum Attack {
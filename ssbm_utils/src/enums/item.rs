@@ -320,6 +320,13 @@ impl Item {
             _ => *self,
         }
     }
+
+    /// Returns true if the item is spawned by a character's moves (e.g. lasers, turnips, Link's
+    /// bombs), rather than being a general item, pokemon, or stage hazard
+    pub fn is_character_item(&self) -> bool {
+        (Item::MARIO_FIRE as u16..=Item::MARIO_LUIGI_COIN as u16).contains(&(*self as u16))
+            || (Item::TURNIP_SMILEY as u16..=Item::SUPER_MISSILE as u16).contains(&(*self as u16))
+    }
 }
 
 #[derive(Debug, Clone, Copy, EnumString, IntoStaticStr, Display, FromRepr, PartialEq, Eq)]